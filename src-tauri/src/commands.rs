@@ -1,9 +1,21 @@
 use tauri::{AppHandle, Manager};
 
-use crate::convert::do_convert;
-use crate::encoder_service::pick_auto_av1_encoder as pick_auto;
-use crate::model::{ConvertRequest, ConvertSummary};
-use crate::state::{cancel_active_conversion, clear_cancel_requested};
+use crate::convert::{do_preview, do_probe, scan_folders as scan};
+use crate::encoder_service::{
+    pick_auto_av1_encoder as pick_auto, refresh_av1_encoders as refresh_checks,
+};
+use crate::model::{
+    ConversionJob, ConversionPreview, ConvertRequest, ConvertSummary, EncoderCheck,
+    FolderScanRequest, FolderScanResult, JobId, MediaProbeSummary,
+};
+use crate::state::{
+    cancel_active_conversion, discard_interrupted_jobs as discard_interrupted,
+    enqueue_jobs as enqueue, list_jobs as list, pause_conversion as pause, remove_job as remove,
+    reorder_jobs as reorder, resume_conversion as resume,
+    resume_interrupted_jobs as resume_interrupted, set_concurrency_limit as set_limit,
+    settle_queued_jobs, stop_job, take_queue_reset_notice as take_reset_notice, wait_for_job,
+    StopReason,
+};
 
 async fn run_blocking<T, F>(task_name: &str, task: F) -> Result<T, String>
 where
@@ -21,21 +33,126 @@ pub async fn pick_auto_av1_encoder(app: AppHandle) -> Result<String, String> {
     run_blocking("Auto-encoder", move || pick_auto(&app_clone)).await
 }
 
+#[tauri::command]
+pub async fn refresh_encoders(app: AppHandle) -> Result<Vec<EncoderCheck>, String> {
+    let app_clone = app.clone();
+    run_blocking("Encoder refresh", move || refresh_checks(&app_clone)).await
+}
+
 #[tauri::command]
 pub async fn convert_video(
     app: AppHandle,
     request: ConvertRequest,
 ) -> Result<ConvertSummary, String> {
-    clear_cancel_requested(&app)?;
+    let job_id = enqueue(&app, vec![request])?
+        .first()
+        .map(|job| job.id)
+        .ok_or_else(|| "Failed to queue the conversion.".to_string())?;
+    let app_clone = app.clone();
+    run_blocking("Conversion", move || wait_for_job(&app_clone, job_id)).await
+}
+
+#[tauri::command]
+pub async fn probe_media(
+    app: AppHandle,
+    request: ConvertRequest,
+) -> Result<MediaProbeSummary, String> {
+    let app_clone = app.clone();
+    run_blocking("Probe", move || do_probe(&app_clone, request)).await
+}
+
+#[tauri::command]
+pub async fn preview_conversion(
+    app: AppHandle,
+    request: ConvertRequest,
+) -> Result<ConversionPreview, String> {
     let app_clone = app.clone();
-    run_blocking("Conversion", move || do_convert(&app_clone, request)).await
+    run_blocking("Preview", move || do_preview(&app_clone, request)).await
+}
+
+#[tauri::command]
+pub async fn scan_folders(
+    app: AppHandle,
+    request: FolderScanRequest,
+) -> Result<FolderScanResult, String> {
+    let app_clone = app.clone();
+    run_blocking("Folder scan", move || scan(&app_clone, &request)).await
+}
+
+#[tauri::command]
+pub fn enqueue_jobs(
+    app: AppHandle,
+    requests: Vec<ConvertRequest>,
+) -> Result<Vec<ConversionJob>, String> {
+    enqueue(&app, requests)
+}
+
+#[tauri::command]
+pub fn list_jobs(app: AppHandle) -> Result<Vec<ConversionJob>, String> {
+    list(&app)
+}
+
+#[tauri::command]
+pub fn remove_job(app: AppHandle, job_id: JobId) -> Result<(), String> {
+    remove(&app, job_id)
+}
+
+#[tauri::command]
+pub fn reorder_jobs(app: AppHandle, job_ids: Vec<JobId>) -> Result<(), String> {
+    reorder(&app, &job_ids)
+}
+
+#[tauri::command]
+pub fn set_concurrency_limit(app: AppHandle, limit: usize) -> Result<(), String> {
+    set_limit(&app, limit)
+}
+
+#[tauri::command]
+pub fn resume_interrupted_jobs(app: AppHandle) -> Result<Vec<ConversionJob>, String> {
+    resume_interrupted(&app)
+}
+
+#[tauri::command]
+pub fn discard_interrupted_jobs(app: AppHandle) -> Result<(), String> {
+    discard_interrupted(&app)
+}
+
+#[tauri::command]
+pub fn take_queue_reset_notice(app: AppHandle) -> Result<Option<String>, String> {
+    take_reset_notice(&app)
 }
 
 #[tauri::command]
 pub fn cancel_conversion(app: AppHandle) -> Result<(), String> {
+    settle_queued_jobs(&app, StopReason::Canceled)?;
     cancel_active_conversion(&app)
 }
 
+#[tauri::command]
+pub fn cancel_job(app: AppHandle, job_id: JobId) -> Result<(), String> {
+    stop_job(&app, job_id, StopReason::Canceled)
+}
+
+#[tauri::command]
+pub fn skip_job(app: AppHandle, job_id: JobId) -> Result<(), String> {
+    stop_job(&app, job_id, StopReason::Skipped)
+}
+
+#[tauri::command]
+pub fn stop_after_current(app: AppHandle) -> Result<(), String> {
+    settle_queued_jobs(&app, StopReason::Stopped)
+}
+
+#[tauri::command]
+pub fn pause_conversion(app: AppHandle, job_id: Option<JobId>) -> Result<(), String> {
+    pause(&app, job_id)
+}
+
+#[tauri::command]
+pub fn resume_conversion(app: AppHandle, job_id: Option<JobId>) -> Result<(), String> {
+    resume(&app, job_id)
+}
+
 #[tauri::command]
 pub fn show_main_window(app: AppHandle) -> Result<(), String> {
     reveal_main_window(&app)
@@ -1,16 +1,39 @@
 use tauri::AppHandle;
 
-use crate::error_protocol::{error_canceled_by_user, is_error_code, ERROR_CODE_CANCELED_BY_USER};
-use crate::state::is_cancel_requested;
+use crate::model::JobId;
+use crate::state::{
+    register_active_conversion, stop_requested, unregister_active_conversion, StopReason,
+};
 
-pub fn abort_if_cancel_requested(app: &AppHandle) -> Result<(), String> {
-    if is_cancel_requested(app) {
-        return Err(error_canceled_by_user());
+pub struct ConversionRegistration {
+    app: AppHandle,
+    job_id: JobId,
+}
+
+impl Drop for ConversionRegistration {
+    fn drop(&mut self) {
+        let _ = unregister_active_conversion(&self.app, self.job_id);
     }
+}
 
-    Ok(())
+pub fn register_conversion(
+    app: &AppHandle,
+    job_id: JobId,
+) -> Result<ConversionRegistration, String> {
+    register_active_conversion(app, job_id)?;
+    Ok(ConversionRegistration {
+        app: app.clone(),
+        job_id,
+    })
+}
+
+pub fn abort_if_stop_requested(app: &AppHandle, job_id: Option<JobId>) -> Result<(), String> {
+    match stop_requested(app, job_id)? {
+        Some(reason) => Err(reason.error()),
+        None => Ok(()),
+    }
 }
 
-pub fn is_canceled_by_user_error(error: &str) -> bool {
-    is_error_code(error, ERROR_CODE_CANCELED_BY_USER)
+pub fn is_stop_error(error: &str) -> bool {
+    StopReason::from_error(error).is_some()
 }
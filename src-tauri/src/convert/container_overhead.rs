@@ -0,0 +1,208 @@
+const MP4_FIXED_OVERHEAD_BYTES: u64 = 1_024;
+const MP4_PER_TRACK_OVERHEAD_BYTES: u64 = 1_024;
+const MP4_PER_PACKET_OVERHEAD_BYTES: u64 = 20;
+const VIDEO_FRAME_RATE_FALLBACK: f64 = 30.0;
+const AUDIO_SAMPLE_RATE_FALLBACK: f64 = 48_000.0;
+const AAC_SAMPLES_PER_PACKET: f64 = 1_024.0;
+const SUBTITLE_PACKETS_PER_SECOND: f64 = 0.5;
+
+pub fn estimate_container_overhead_bytes(track_count: usize, packet_count: u64) -> u64 {
+    MP4_FIXED_OVERHEAD_BYTES
+        + MP4_PER_TRACK_OVERHEAD_BYTES * track_count as u64
+        + MP4_PER_PACKET_OVERHEAD_BYTES * packet_count
+}
+
+pub fn estimate_video_packets(
+    frame_count: Option<u64>,
+    frame_rate: Option<f64>,
+    duration_sec: f64,
+) -> u64 {
+    frame_count.unwrap_or_else(|| {
+        (duration_sec * frame_rate.unwrap_or(VIDEO_FRAME_RATE_FALLBACK)).ceil() as u64
+    })
+}
+
+pub fn estimate_aac_packets(sample_rate: Option<u32>, duration_sec: f64) -> u64 {
+    let sample_rate = sample_rate
+        .map(f64::from)
+        .unwrap_or(AUDIO_SAMPLE_RATE_FALLBACK);
+    (duration_sec * sample_rate / AAC_SAMPLES_PER_PACKET).ceil() as u64
+}
+
+pub fn estimate_subtitle_packets(frame_count: Option<u64>, duration_sec: f64) -> u64 {
+    frame_count.unwrap_or_else(|| (duration_sec * SUBTITLE_PACKETS_PER_SECOND).ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::{
+        estimate_aac_packets, estimate_container_overhead_bytes, estimate_subtitle_packets,
+        estimate_video_packets,
+    };
+    use crate::ffmpeg::{list_encoders, video_preset_args, MIN_PRESET_LEVEL};
+    use crate::test_fs::scratch_dir;
+
+    #[test]
+    fn estimate_video_packets_prefers_probed_frame_count() {
+        assert_eq!(estimate_video_packets(Some(250), Some(25.0), 20.0), 250);
+        assert_eq!(estimate_video_packets(None, Some(29.97), 10.0), 300);
+        assert_eq!(estimate_video_packets(None, None, 2.0), 60);
+    }
+
+    #[test]
+    fn estimate_aac_packets_uses_aac_frame_size() {
+        assert_eq!(estimate_aac_packets(Some(48_000), 10.0), 469);
+        assert_eq!(estimate_aac_packets(Some(44_100), 1.0), 44);
+        assert_eq!(estimate_aac_packets(None, 1.0), 47);
+    }
+
+    #[test]
+    fn estimate_subtitle_packets_falls_back_to_duration() {
+        assert_eq!(estimate_subtitle_packets(Some(12), 60.0), 12);
+        assert_eq!(estimate_subtitle_packets(None, 60.0), 30);
+    }
+
+    #[test]
+    fn estimate_container_overhead_bytes_grows_with_tracks_and_packets() {
+        let video_only = estimate_container_overhead_bytes(1, 300);
+        let with_audio = estimate_container_overhead_bytes(2, 300 + 469);
+
+        assert!(with_audio > video_only);
+        assert_eq!(video_only, 1_024 + 1_024 + 300 * 20);
+    }
+
+    const SOFTWARE_AV1_ENCODERS: &[&str] = &["libsvtav1", "libaom-av1", "librav1e"];
+    const OVERHEAD_CLIPS: &[(f64, u32)] = &[(2.0, 24), (5.0, 30), (10.0, 60)];
+
+    fn tool_available(program: &str) -> bool {
+        Command::new(program)
+            .arg("-version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    fn software_av1_encoder() -> Option<String> {
+        let available = list_encoders(Path::new("ffmpeg")).ok()?;
+        SOFTWARE_AV1_ENCODERS
+            .iter()
+            .find(|encoder| available.iter().any(|name| name == *encoder))
+            .map(|encoder| encoder.to_string())
+    }
+
+    fn run_tool(program: &str, args: &[&str]) -> String {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .unwrap_or_else(|e| panic!("failed to run {program}: {e}"));
+        assert!(
+            output.status.success(),
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    fn count_packets(output_path: &str, stream: &str) -> (u64, u64) {
+        let sizes = run_tool(
+            "ffprobe",
+            &[
+                "-v",
+                "error",
+                "-select_streams",
+                stream,
+                "-show_entries",
+                "packet=size",
+                "-of",
+                "csv=p=0",
+                output_path,
+            ],
+        );
+        sizes
+            .lines()
+            .filter_map(|line| line.trim().parse::<u64>().ok())
+            .fold((0, 0), |(count, total), size| (count + 1, total + size))
+    }
+
+    #[test]
+    fn estimate_container_overhead_bytes_covers_real_av1_encodes() {
+        if !tool_available("ffmpeg") || !tool_available("ffprobe") {
+            eprintln!("skipping: ffmpeg and ffprobe are not on PATH");
+            return;
+        }
+        let Some(encoder) = software_av1_encoder() else {
+            eprintln!("skipping: ffmpeg has no software AV1 encoder");
+            return;
+        };
+
+        let dir = scratch_dir("overhead");
+        for &(duration_sec, frame_rate) in OVERHEAD_CLIPS {
+            let output = dir.join(format!("{duration_sec}-{frame_rate}.mp4"));
+            let output_path = output.to_string_lossy().to_string();
+            let video_source =
+                format!("testsrc2=size=320x180:rate={frame_rate}:duration={duration_sec}");
+            let audio_source =
+                format!("sine=frequency=440:sample_rate=48000:duration={duration_sec}");
+            let mut args = vec![
+                "-v".to_string(),
+                "error".to_string(),
+                "-y".to_string(),
+                "-f".to_string(),
+                "lavfi".to_string(),
+                "-i".to_string(),
+                video_source,
+                "-f".to_string(),
+                "lavfi".to_string(),
+                "-i".to_string(),
+                audio_source,
+                "-pix_fmt".to_string(),
+                "yuv420p".to_string(),
+                "-c:v".to_string(),
+                encoder.clone(),
+            ];
+            args.extend(video_preset_args(MIN_PRESET_LEVEL, &encoder, None));
+            args.extend(
+                [
+                    "-b:v",
+                    "300k",
+                    "-c:a",
+                    "aac",
+                    "-b:a",
+                    "96k",
+                    "-movflags",
+                    "+faststart",
+                    &output_path,
+                ]
+                .map(str::to_string),
+            );
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            run_tool("ffmpeg", &args);
+
+            let (video_packets, video_bytes) = count_packets(&output_path, "v:0");
+            let (audio_packets, audio_bytes) = count_packets(&output_path, "a:0");
+            let file_size = std::fs::metadata(Path::new(&output_path))
+                .expect("encoded file should exist")
+                .len();
+            let _ = std::fs::remove_file(&output);
+
+            let measured = file_size - video_bytes - audio_bytes;
+            let predicted_packets =
+                estimate_video_packets(None, Some(frame_rate as f64), duration_sec)
+                    + estimate_aac_packets(Some(48_000), duration_sec);
+            let estimated = estimate_container_overhead_bytes(2, predicted_packets);
+
+            assert!(
+                predicted_packets.abs_diff(video_packets + audio_packets) <= 2,
+                "{encoder} {duration_sec}s @ {frame_rate} fps: packet estimate {predicted_packets} vs measured {}",
+                video_packets + audio_packets
+            );
+            assert!(
+                estimated >= measured && estimated <= measured + measured / 2,
+                "{encoder} {duration_sec}s @ {frame_rate} fps: estimated {estimated} bytes, measured {measured} bytes"
+            );
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
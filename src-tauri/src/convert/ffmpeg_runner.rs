@@ -1,17 +1,17 @@
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use std::process::Stdio;
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
 use tauri::{AppHandle, Emitter};
 
-use crate::error_protocol::error_canceled_by_user;
 use crate::ffmpeg::hidden_command;
-use crate::model::ProgressEvent;
+use crate::model::{JobId, ProgressEvent};
 use crate::state::{
-    is_cancel_requested, register_active_conversion, register_ffmpeg_pid,
-    unregister_active_conversion, unregister_ffmpeg_pid,
+    get_active_conversion, register_ffmpeg_pid, stop_requested, unregister_ffmpeg_pid,
+    ConversionControl,
 };
 
 const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(120);
@@ -24,35 +24,22 @@ struct FfmpegPidGuard {
 
 impl Drop for FfmpegPidGuard {
     fn drop(&mut self) {
-        if let Err(error) = unregister_ffmpeg_pid(&self.app, self.pid) {
-            eprintln!("Failed to unregister ffmpeg pid {}: {error}", self.pid);
-        }
+        let _ = unregister_ffmpeg_pid(&self.app, self.pid);
     }
 }
 
-struct ActiveConversionGuard {
-    app: AppHandle,
+struct AttachedProcessGuard {
+    control: Arc<ConversionControl>,
     pid: u32,
 }
 
-impl Drop for ActiveConversionGuard {
+impl Drop for AttachedProcessGuard {
     fn drop(&mut self) {
-        if let Err(error) = unregister_active_conversion(&self.app, self.pid) {
-            eprintln!(
-                "Failed to unregister active conversion {}: {error}",
-                self.pid
-            );
-        }
+        let _ = self.control.detach_process(self.pid);
     }
 }
 
-pub fn run_ffmpeg_with_progress(
-    app: &AppHandle,
-    ffmpeg_path: &Path,
-    args: &[String],
-    duration_sec: f64,
-    label: &str,
-) -> Result<(), String> {
+pub fn with_progress_args(args: &[String]) -> Vec<String> {
     let mut full_args = args.to_vec();
     full_args.extend_from_slice(&[
         "-progress".to_string(),
@@ -61,6 +48,46 @@ pub fn run_ffmpeg_with_progress(
         "-loglevel".to_string(),
         "error".to_string(),
     ]);
+    full_args
+}
+
+fn control_ref(guard: &Option<AttachedProcessGuard>) -> Option<&ConversionControl> {
+    guard.as_ref().map(|guard| guard.control.as_ref())
+}
+
+fn paused_duration(control: Option<&ConversionControl>) -> Duration {
+    control
+        .map(ConversionControl::paused_duration)
+        .unwrap_or_default()
+}
+
+fn encode_speed(out_time_sec: f64, active_elapsed: Duration) -> f64 {
+    let elapsed_sec = active_elapsed.as_secs_f64();
+    if elapsed_sec <= 0.0 {
+        return 0.0;
+    }
+    out_time_sec.max(0.0) / elapsed_sec
+}
+
+fn remaining_seconds(duration_sec: f64, out_time_sec: f64, speed: f64) -> Option<f64> {
+    if speed <= 0.0 {
+        return None;
+    }
+    Some((duration_sec - out_time_sec).max(0.0) / speed)
+}
+
+pub fn run_ffmpeg_with_progress(
+    app: &AppHandle,
+    job_id: Option<JobId>,
+    ffmpeg_path: &Path,
+    args: &[String],
+    duration_sec: f64,
+    label: &str,
+) -> Result<(), String> {
+    let full_args = with_progress_args(args);
+    let control = job_id
+        .map(|job_id| get_active_conversion(app, job_id))
+        .transpose()?;
 
     let mut child = hidden_command(ffmpeg_path)
         .args(full_args)
@@ -81,14 +108,16 @@ pub fn run_ffmpeg_with_progress(
         .stdin
         .take()
         .ok_or_else(|| "No ffmpeg stdin.".to_string())?;
-    if let Err(error) = register_active_conversion(app, pid, stdin) {
-        let _ = child.kill();
-        let _ = child.wait();
-        return Err(error);
-    }
-    let _active_guard = ActiveConversionGuard {
-        app: app.clone(),
-        pid,
+    let process_guard = match control {
+        Some(control) => {
+            if let Err(error) = control.attach_process(pid, stdin) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(error);
+            }
+            Some(AttachedProcessGuard { control, pid })
+        }
+        None => None,
     };
 
     let stdout = child
@@ -106,6 +135,8 @@ pub fn run_ffmpeg_with_progress(
         text
     });
 
+    let started_at = Instant::now();
+    let paused_before = paused_duration(control_ref(&process_guard));
     let mut out_time_sec = 0.0_f64;
     let mut speed = 0.0_f64;
     let mut last_emit_at = Instant::now()
@@ -123,25 +154,16 @@ pub fn run_ffmpeg_with_progress(
             continue;
         }
 
-        if let Some(value) = line.strip_prefix("speed=") {
-            if let Some(raw_speed) = value.strip_suffix('x') {
-                if let Ok(parsed_speed) = raw_speed.trim().parse::<f64>() {
-                    speed = parsed_speed;
-                }
-            }
-            continue;
-        }
-
         if line.trim() != "progress=continue" || duration_sec <= 0.0 {
             continue;
         }
 
+        let control = control_ref(&process_guard);
+        let paused_time = paused_duration(control).saturating_sub(paused_before);
+        let active_elapsed = started_at.elapsed().saturating_sub(paused_time);
+        speed = encode_speed(out_time_sec, active_elapsed);
         let percent = (out_time_sec / duration_sec * 100.0).clamp(0.0, 100.0);
-        let eta = if speed > 0.0 {
-            Some(((duration_sec - out_time_sec).max(0.0)) / speed)
-        } else {
-            None
-        };
+        let eta = remaining_seconds(duration_sec, out_time_sec, speed);
 
         let now = Instant::now();
         let percent_advanced = percent - last_emitted_percent;
@@ -153,11 +175,16 @@ pub fn run_ffmpeg_with_progress(
         }
 
         let payload = ProgressEvent {
+            job_id,
             percent,
             speed: if speed > 0.0 { Some(speed) } else { None },
             eta_seconds: eta,
             label: label_owned.clone(),
+            paused: control.is_some_and(|control| control.is_paused()),
         };
+        if let Some(control) = control {
+            control.record_progress(&payload);
+        }
         app.emit("convert-progress", payload)
             .map_err(|e| format!("Failed to emit progress event: {e}"))?;
         last_emit_at = now;
@@ -172,8 +199,8 @@ pub fn run_ffmpeg_with_progress(
         .unwrap_or_else(|_| "Failed to read ffmpeg stderr.".into());
 
     if !status.success() {
-        if is_cancel_requested(app) {
-            return Err(error_canceled_by_user());
+        if let Some(reason) = stop_requested(app, job_id)? {
+            return Err(reason.error());
         }
         return Err(format!("ffmpeg failed: {stderr_text}"));
     }
@@ -181,13 +208,37 @@ pub fn run_ffmpeg_with_progress(
     app.emit(
         "convert-progress",
         ProgressEvent {
+            job_id,
             percent: 100.0,
             speed: Some(speed),
             eta_seconds: Some(0.0),
             label: label_owned,
+            paused: false,
         },
     )
     .map_err(|e| format!("Failed to emit progress event: {e}"))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{encode_speed, remaining_seconds};
+
+    #[test]
+    fn speed_uses_active_time_only() {
+        let wall_clock = Duration::from_secs(30);
+        let paused = Duration::from_secs(20);
+
+        assert_eq!(encode_speed(20.0, wall_clock - paused), 2.0);
+        assert_eq!(remaining_seconds(100.0, 20.0, 2.0), Some(40.0));
+    }
+
+    #[test]
+    fn eta_is_unknown_before_any_progress() {
+        assert_eq!(encode_speed(0.0, Duration::ZERO), 0.0);
+        assert_eq!(remaining_seconds(100.0, 0.0, 0.0), None);
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+
+use crate::ffmpeg::{check_video_file, resolve_tool_path};
+use crate::model::{FolderScanRequest, FolderScanResult, ScannedFile, SkippedFile};
+
+use super::path_glob::glob_matches;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "mov", "avi", "webm", "m4v", "ts"];
+
+struct Candidate {
+    path: PathBuf,
+    source_root: String,
+    size_bytes: u64,
+}
+
+pub fn scan_folders(
+    app: &AppHandle,
+    request: &FolderScanRequest,
+) -> Result<FolderScanResult, String> {
+    if request.folders.is_empty() {
+        return Err("Choose at least one folder to scan.".to_string());
+    }
+
+    let (candidates, mut skipped) = collect_candidates(request)?;
+    let ffprobe_path = resolve_tool_path(app, "ffprobe.exe")?;
+    let mut files = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        let input_path = candidate.path.to_string_lossy().to_string();
+        let check = match check_video_file(&ffprobe_path, &input_path) {
+            Ok(check) => check,
+            Err(error) => {
+                skipped.push(skip(&candidate.path, error));
+                continue;
+            }
+        };
+        if request.skip_converted && check.converted_output {
+            skipped.push(skip(
+                &candidate.path,
+                "Already a converted output.".to_string(),
+            ));
+            continue;
+        }
+        if !check.has_video {
+            skipped.push(skip(&candidate.path, "No video stream.".to_string()));
+            continue;
+        }
+        if let (Some(min_duration_sec), Some(duration_sec)) =
+            (request.min_duration_sec, check.duration_sec)
+        {
+            if duration_sec < min_duration_sec {
+                skipped.push(skip(
+                    &candidate.path,
+                    format!("Shorter than {min_duration_sec}s ({duration_sec:.1}s)."),
+                ));
+                continue;
+            }
+        }
+
+        files.push(ScannedFile {
+            input_path,
+            source_root: candidate.source_root,
+            size_bytes: candidate.size_bytes,
+            duration_sec: check.duration_sec,
+        });
+    }
+
+    Ok(FolderScanResult { files, skipped })
+}
+
+fn collect_candidates(
+    request: &FolderScanRequest,
+) -> Result<(Vec<Candidate>, Vec<SkippedFile>), String> {
+    let extensions: Vec<String> = if request.extensions.is_empty() {
+        VIDEO_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()
+    } else {
+        request
+            .extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_ascii_lowercase())
+            .collect()
+    };
+
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::new();
+    let mut skipped = Vec::new();
+    for folder in &request.folders {
+        let root = Path::new(folder);
+        if !root.is_dir() {
+            return Err(format!("Folder not found: {folder}"));
+        }
+
+        let mut files = Vec::new();
+        collect_files(root, request.recursive, &mut files, &mut skipped);
+        files.sort();
+        for path in files {
+            if !seen.insert(path.clone()) || !has_extension(&path, &extensions) {
+                continue;
+            }
+            let relative_path = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            let included = request.include_globs.is_empty()
+                || request
+                    .include_globs
+                    .iter()
+                    .any(|pattern| glob_matches(pattern, &relative_path));
+            let excluded = request
+                .exclude_globs
+                .iter()
+                .any(|pattern| glob_matches(pattern, &relative_path));
+            if !included || excluded {
+                continue;
+            }
+
+            let size_bytes = fs::metadata(&path)
+                .map_err(|e| format!("Could not read {}: {e}", path.to_string_lossy()))?
+                .len();
+            if let Some(min_size_bytes) = request.min_size_bytes {
+                if size_bytes < min_size_bytes {
+                    skipped.push(skip(
+                        &path,
+                        format!("Smaller than {min_size_bytes} bytes ({size_bytes} bytes)."),
+                    ));
+                    continue;
+                }
+            }
+
+            candidates.push(Candidate {
+                path,
+                source_root: folder.clone(),
+                size_bytes,
+            });
+        }
+    }
+    Ok((candidates, skipped))
+}
+
+fn collect_files(
+    dir: &Path,
+    recursive: bool,
+    files: &mut Vec<PathBuf>,
+    skipped: &mut Vec<SkippedFile>,
+) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            skipped.push(skip(dir, format!("Could not read folder: {e}")));
+            return;
+        }
+    };
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                skipped.push(skip(dir, format!("Could not read folder entry: {e}")));
+                continue;
+            }
+        };
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                if recursive {
+                    collect_files(&path, recursive, files, skipped);
+                }
+            }
+            Ok(file_type) if file_type.is_file() => files.push(path),
+            Ok(_) => {}
+            Err(e) => skipped.push(skip(&path, format!("Could not read file type: {e}"))),
+        }
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|extension| extensions.contains(&extension))
+}
+
+fn skip(path: &Path, reason: String) -> SkippedFile {
+    SkippedFile {
+        path: path.to_string_lossy().to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{collect_candidates, collect_files};
+    use crate::model::FolderScanRequest;
+    use crate::test_fs::scratch_dir;
+
+    fn scan_request(root: &Path) -> FolderScanRequest {
+        FolderScanRequest {
+            folders: vec![root.to_string_lossy().to_string()],
+            recursive: true,
+            extensions: Vec::new(),
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            min_size_bytes: None,
+            min_duration_sec: None,
+            skip_converted: true,
+        }
+    }
+
+    fn footage_tree(name: &str) -> PathBuf {
+        let root = scratch_dir(&format!("folder-scan-{name}"));
+        fs::create_dir_all(root.join("day 1").join("proxies")).expect("tree should be created");
+        for (file, size) in [
+            ("intro.MKV", 2_000),
+            ("notes.txt", 2_000),
+            ("intro.av1.mp4", 1_000),
+            ("intro.av1 (2).mp4", 1_000),
+            ("day 1/take-1080p.mp4", 1_000),
+            ("day 1/take.mov", 5_000),
+            ("day 1/tiny.mp4", 10),
+            ("day 1/proxies/take.mp4", 500),
+        ] {
+            fs::write(root.join(file), vec![0_u8; size]).expect("file should be written");
+        }
+        root
+    }
+
+    fn relative_names(root: &Path, paths: impl Iterator<Item = String>) -> Vec<String> {
+        paths
+            .map(|path| {
+                Path::new(&path)
+                    .strip_prefix(root)
+                    .expect("path should be under root")
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn collect_candidates_applies_filters_and_reports_skips() {
+        let root = footage_tree("filters");
+        let mut request = scan_request(&root);
+        request.exclude_globs = vec!["**/proxies/**".to_string()];
+        request.min_size_bytes = Some(100);
+
+        let result = collect_candidates(&request);
+        let _ = fs::remove_dir_all(&root);
+        let (candidates, skipped) = result.expect("tree should be scanned");
+
+        assert_eq!(
+            relative_names(
+                &root,
+                candidates
+                    .iter()
+                    .map(|candidate| candidate.path.to_string_lossy().to_string())
+            ),
+            [
+                "day 1/take-1080p.mp4",
+                "day 1/take.mov",
+                "intro.MKV",
+                "intro.av1 (2).mp4",
+                "intro.av1.mp4"
+            ],
+            "files named like outputs wait for the marker check"
+        );
+        assert_eq!(
+            relative_names(&root, skipped.iter().map(|file| file.path.clone())),
+            ["day 1/tiny.mp4"]
+        );
+    }
+
+    #[test]
+    fn collect_candidates_honors_recursion_and_include_rules() {
+        let root = footage_tree("include");
+        let mut request = scan_request(&root);
+        request.recursive = false;
+        request.extensions = vec![".mkv".to_string()];
+        let flat = collect_candidates(&request).map(|(candidates, _)| candidates.len());
+
+        let mut request = scan_request(&root);
+        request.include_globs = vec!["day 1/*.mov".to_string()];
+        let included = collect_candidates(&request).map(|(candidates, _)| candidates.len());
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(flat, Ok(1));
+        assert_eq!(included, Ok(1));
+    }
+
+    #[test]
+    fn unreadable_folder_is_reported_and_scanning_continues() {
+        let root = footage_tree("unreadable");
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        collect_files(&root.join("missing"), true, &mut files, &mut skipped);
+        collect_files(&root.join("day 1"), true, &mut files, &mut skipped);
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].reason.starts_with("Could not read folder"));
+        assert_eq!(files.len(), 4);
+    }
+}
@@ -0,0 +1,30 @@
+use std::thread;
+
+use tauri::AppHandle;
+
+use crate::error_protocol::{is_error_code, ERROR_CODE_OUTPUT_EXISTS};
+use crate::model::JobStatus;
+use crate::state::{finish_job, wait_for_next_job, StopReason};
+
+use super::do_convert;
+
+pub fn start_job_worker(app: AppHandle) {
+    thread::spawn(move || loop {
+        let Ok(job) = wait_for_next_job(&app) else {
+            return;
+        };
+
+        let app = app.clone();
+        thread::spawn(move || {
+            let outcome = do_convert(&app, job.request, job.id);
+            let status = match &outcome {
+                Ok(_) => JobStatus::Succeeded,
+                Err(error) if is_error_code(error, ERROR_CODE_OUTPUT_EXISTS) => JobStatus::Skipped,
+                Err(error) => StopReason::from_error(error)
+                    .map(StopReason::status)
+                    .unwrap_or(JobStatus::Failed),
+            };
+            let _ = finish_job(&app, job.id, status, outcome);
+        });
+    });
+}
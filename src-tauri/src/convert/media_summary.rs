@@ -0,0 +1,96 @@
+use crate::ffmpeg::{MediaInfo, StreamInfo, StreamKind};
+use crate::model::{
+    AudioTrackSummary, MediaProbeSummary, PlannedOutput, SubtitleTrackSummary, VideoTrackSummary,
+};
+
+use super::planning::ConversionPlan;
+use super::stream_selection::select_output_streams;
+
+pub fn summarize_media(
+    input_path: &str,
+    media: &MediaInfo,
+    plan: Result<ConversionPlan, String>,
+) -> Result<MediaProbeSummary, String> {
+    let video = media
+        .primary_video()
+        .ok_or_else(|| "Input has no video stream.".to_string())?;
+
+    let audio_tracks = media
+        .streams_of(StreamKind::Audio)
+        .map(|stream| AudioTrackSummary {
+            index: stream.index,
+            codec: stream.codec_name.clone(),
+            language: stream.language.clone(),
+            title: stream.title.clone(),
+            channels: stream.audio.as_ref().and_then(|audio| audio.channels),
+            bit_rate_kbps: stream.bit_rate_kbps,
+            default: stream.disposition.default,
+        })
+        .collect();
+
+    let kept_subtitles: Vec<u32> = select_output_streams(media)
+        .map(|streams| {
+            streams
+                .subtitle_tracks
+                .iter()
+                .map(|track| track.input_index)
+                .collect()
+        })
+        .unwrap_or_default();
+    let subtitle_tracks = media
+        .streams_of(StreamKind::Subtitle)
+        .map(|stream| SubtitleTrackSummary {
+            index: stream.index,
+            codec: stream.codec_name.clone(),
+            language: stream.language.clone(),
+            title: stream.title.clone(),
+            forced: stream.disposition.forced,
+            kept: kept_subtitles.contains(&stream.index),
+        })
+        .collect();
+
+    let (planned_output, plan_error) = match plan {
+        Ok(plan) => (Some(planned_output(&plan)), None),
+        Err(error) => (None, Some(error)),
+    };
+
+    Ok(MediaProbeSummary {
+        input_path: input_path.to_string(),
+        container: media.format_name.clone(),
+        duration_sec: media.duration_sec,
+        size_bytes: media.size_bytes,
+        bit_rate_kbps: media.bit_rate_kbps,
+        video: summarize_video(video),
+        audio_tracks,
+        subtitle_tracks,
+        chapter_count: media.chapters.len(),
+        planned_output,
+        plan_error,
+    })
+}
+
+fn planned_output(plan: &ConversionPlan) -> PlannedOutput {
+    PlannedOutput {
+        output_path: plan.output_path.to_string_lossy().to_string(),
+        target_policy: plan.target_policy(),
+        target_size_bytes: plan.target_size_bytes(),
+        quality_level: plan.quality_level(),
+        video_bitrate_kbps: plan.video_bitrate_kbps(),
+        audio_bitrate_kbps: plan.output_streams.audio_bitrate_kbps(),
+        encoder_candidates: plan.encoder_candidates.clone(),
+        two_pass: plan.two_pass,
+    }
+}
+
+fn summarize_video(stream: &StreamInfo) -> VideoTrackSummary {
+    let details = stream.video.as_ref();
+    VideoTrackSummary {
+        index: stream.index,
+        codec: stream.codec_name.clone(),
+        width: details.map(|video| video.width).unwrap_or_default(),
+        height: details.map(|video| video.height).unwrap_or_default(),
+        frame_rate: details.and_then(|video| video.frame_rate),
+        bit_rate_kbps: stream.bit_rate_kbps,
+        hdr: details.is_some_and(|video| video.hdr),
+    }
+}
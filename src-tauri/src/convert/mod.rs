@@ -1,40 +1,87 @@
 mod cancellation;
+mod container_overhead;
 mod ffmpeg_runner;
+mod folder_scan;
+mod job_worker;
+mod media_summary;
+mod output_path;
+mod output_template;
+mod path_glob;
 mod planning;
+mod preview;
+mod scratch_dir;
+mod source_tree;
+mod stream_selection;
+mod temp_output;
+mod two_pass;
+mod verification;
+mod vmaf_search;
+
+use std::fs;
+use std::path::Path;
 
 use tauri::AppHandle;
 
-use crate::model::{ConvertRequest, ConvertSummary};
+use crate::error_protocol::error_target_not_met;
+use crate::ffmpeg::{encoder_backend, probe_media_info, resolve_tool_path};
+use crate::model::{
+    ConversionPreview, ConvertRequest, ConvertSummary, EncodeAttempt, JobId, MediaProbeSummary,
+    VmafSearchResult,
+};
+use crate::state::{reserve_partial_output, with_partial_output_claims};
 
-use self::cancellation::{abort_if_cancel_requested, is_canceled_by_user_error};
+use self::cancellation::{abort_if_stop_requested, is_stop_error, register_conversion};
 use self::ffmpeg_runner::run_ffmpeg_with_progress;
-use self::planning::{build_conversion_plan, build_encode_args};
+use self::media_summary::summarize_media;
+use self::planning::{
+    build_conversion_plan, build_probe_plan, correct_video_bitrate_kbps, single_pass_step,
+    ConversionPlan, RateControl,
+};
+use self::preview::preview_plan;
+use self::temp_output::TempOutput;
+use self::two_pass::run_two_pass_encode;
+use self::verification::{is_within_target, measure_output_size};
+use self::vmaf_search::search_vmaf_quality_level;
+
+pub use self::folder_scan::scan_folders;
+pub use self::job_worker::start_job_worker;
 
-pub fn do_convert(app: &AppHandle, request: ConvertRequest) -> Result<ConvertSummary, String> {
-    let plan = build_conversion_plan(app, &request)?;
+pub fn do_convert(
+    app: &AppHandle,
+    request: ConvertRequest,
+    job_id: JobId,
+) -> Result<ConvertSummary, String> {
+    let _registration = register_conversion(app, job_id)?;
+    let mut plan = build_conversion_plan(app, &request, job_id)?;
+    create_output_dir(&plan.temp_output_path)?;
+    reserve_partial_output(app, job_id, |is_claimed| plan.reserve_output(is_claimed))?;
+    let temp_output = TempOutput::new(&plan.temp_output_path);
 
-    let mut selected_encoder: Option<String> = None;
+    let mut selected_encoder: Option<(String, bool)> = None;
+    let mut vmaf_search: Option<VmafSearchResult> = None;
     let mut last_error = String::new();
 
-    for encoder in &plan.encoder_candidates {
-        abort_if_cancel_requested(app)?;
+    for encoder in plan.encoder_candidates.clone() {
+        abort_if_stop_requested(app, plan.job_id)?;
 
-        let args = build_encode_args(&plan, encoder);
-        match run_ffmpeg_with_progress(app, &plan.ffmpeg_path, &args, plan.duration_sec, "Encode") {
-            Ok(()) => {
-                selected_encoder = Some(encoder.clone());
+        match search_and_encode(app, &mut plan, &encoder) {
+            Ok((two_pass, search)) => {
+                selected_encoder = Some((encoder, two_pass));
+                vmaf_search = search;
                 break;
             }
             Err(error) => {
-                if is_canceled_by_user_error(&error) {
+                if is_stop_error(&error) {
                     return Err(error);
                 }
-                last_error = format!("{encoder}: {error}");
+                temp_output.truncate();
+                let failure = encoder_backend(&encoder).classify_error(&error);
+                last_error = format!("{encoder} ({failure}): {error}");
             }
         }
     }
 
-    let selected_encoder = selected_encoder.ok_or_else(|| {
+    let (selected_encoder, two_pass) = selected_encoder.ok_or_else(|| {
         format!(
             "All AV1 encoder attempts failed ({}). Last error: {}",
             plan.encoder_candidates.join(", "),
@@ -42,11 +89,169 @@ pub fn do_convert(app: &AppHandle, request: ConvertRequest) -> Result<ConvertSum
         )
     })?;
 
+    let (output_size_bytes, attempts) = match plan.rate_control {
+        RateControl::TargetSize { .. } => enforce_size_target(app, &mut plan, &selected_encoder)?,
+        RateControl::ConstantQuality { .. } => {
+            (measure_output_size(&plan.temp_output_path)?, Vec::new())
+        }
+    };
+    abort_if_stop_requested(app, plan.job_id)?;
+    let _retarget_reservation = if plan.encoder_candidates.first() != Some(&selected_encoder) {
+        with_partial_output_claims(app, job_id, |is_claimed| {
+            plan.retarget_output(&selected_encoder, is_claimed)
+        })?
+        .map(|reservation| TempOutput::new(&reservation))
+    } else {
+        None
+    };
+    temp_output.publish(&plan.output_path)?;
+
     Ok(ConvertSummary {
         output_path: plan.output_path.to_string_lossy().to_string(),
-        target_size_bytes: plan.target_size_bytes,
-        audio_bitrate_kbps: plan.audio_bitrate_kbps,
-        video_bitrate_kbps: plan.video_bitrate_kbps,
+        target_policy: plan.target_policy(),
+        target_size_bytes: plan.target_size_bytes(),
+        quality_level: plan.quality_level(),
+        output_size_bytes,
+        audio_bitrate_kbps: plan.output_streams.audio_bitrate_kbps(),
+        audio_track_count: plan.output_streams.audio_tracks.len(),
+        subtitle_track_count: plan.output_streams.subtitle_tracks.len(),
+        video_bitrate_kbps: plan.video_bitrate_kbps(),
         av1_encoder: selected_encoder,
+        two_pass,
+        attempts,
+        vmaf_search,
+    })
+}
+
+pub fn do_probe(app: &AppHandle, request: ConvertRequest) -> Result<MediaProbeSummary, String> {
+    if !Path::new(&request.input_path).exists() {
+        return Err(format!("Input file not found: {}", request.input_path));
+    }
+    let ffprobe_path = resolve_tool_path(app, "ffprobe.exe")?;
+    let media = probe_media_info(&ffprobe_path, &request.input_path)?;
+    summarize_media(&request.input_path, &media, build_probe_plan(app, &request))
+}
+
+pub fn do_preview(app: &AppHandle, request: ConvertRequest) -> Result<ConversionPreview, String> {
+    let plan = build_probe_plan(app, &request)?;
+    Ok(preview_plan(&plan))
+}
+
+fn search_and_encode(
+    app: &AppHandle,
+    plan: &mut ConversionPlan,
+    encoder: &str,
+) -> Result<(bool, Option<VmafSearchResult>), String> {
+    let search = match plan.vmaf_target {
+        Some(target_score) => {
+            let result = search_vmaf_quality_level(app, plan, encoder, target_score)?;
+            plan.set_quality_level(result.quality_level);
+            Some(result)
+        }
+        None => None,
+    };
+
+    let two_pass = encode_with_encoder(app, plan, encoder, "")?;
+    Ok((two_pass, search))
+}
+
+fn enforce_size_target(
+    app: &AppHandle,
+    plan: &mut ConversionPlan,
+    encoder: &str,
+) -> Result<(u64, Vec<EncodeAttempt>), String> {
+    let (Some(target_size_bytes), Some(mut video_bitrate_kbps)) =
+        (plan.target_size_bytes(), plan.video_bitrate_kbps())
+    else {
+        return Err("Size verification requires a target-size plan.".to_string());
+    };
+
+    let mut attempts = Vec::new();
+    let mut attempt = verify_attempt(plan, 1, video_bitrate_kbps, target_size_bytes)?;
+    while !attempt.within_target && attempt.attempt < plan.max_encode_attempts {
+        abort_if_stop_requested(app, plan.job_id)?;
+
+        video_bitrate_kbps = correct_video_bitrate_kbps(
+            video_bitrate_kbps,
+            target_size_bytes,
+            attempt.output_size_bytes,
+            plan.fixed_output_bytes(),
+        )
+        .map_err(|error| {
+            format!(
+                "Output overshot the target ({} > {target_size_bytes} bytes) and cannot be re-encoded: {error}",
+                attempt.output_size_bytes
+            )
+        })?;
+        plan.set_video_bitrate_kbps(video_bitrate_kbps);
+
+        let next_attempt = attempt.attempt + 1;
+        attempts.push(attempt);
+
+        let label_suffix = format!(" (attempt {next_attempt}/{})", plan.max_encode_attempts);
+        encode_with_encoder(app, plan, encoder, &label_suffix)?;
+        attempt = verify_attempt(plan, next_attempt, video_bitrate_kbps, target_size_bytes)?;
+    }
+
+    if !attempt.within_target {
+        return Err(error_target_not_met(
+            attempt.attempt,
+            attempt.output_size_bytes,
+            target_size_bytes,
+        ));
+    }
+
+    let output_size_bytes = attempt.output_size_bytes;
+    attempts.push(attempt);
+    Ok((output_size_bytes, attempts))
+}
+
+fn encode_with_encoder(
+    app: &AppHandle,
+    plan: &ConversionPlan,
+    encoder: &str,
+    label_suffix: &str,
+) -> Result<bool, String> {
+    if plan.uses_two_pass(encoder) {
+        run_two_pass_encode(app, plan, encoder, label_suffix)?;
+        return Ok(true);
+    }
+
+    let step = single_pass_step(plan, encoder, label_suffix);
+    run_ffmpeg_with_progress(
+        app,
+        plan.job_id,
+        &plan.ffmpeg_path,
+        &step.args,
+        plan.duration_sec,
+        &step.label,
+    )?;
+    Ok(false)
+}
+
+fn verify_attempt(
+    plan: &ConversionPlan,
+    attempt: u32,
+    video_bitrate_kbps: u32,
+    target_size_bytes: u64,
+) -> Result<EncodeAttempt, String> {
+    let output_size_bytes = measure_output_size(&plan.temp_output_path)?;
+    Ok(EncodeAttempt {
+        attempt,
+        video_bitrate_kbps,
+        output_size_bytes,
+        within_target: is_within_target(output_size_bytes, target_size_bytes),
+    })
+}
+
+fn create_output_dir(output_path: &Path) -> Result<(), String> {
+    let Some(output_dir) = output_path.parent() else {
+        return Ok(());
+    };
+    fs::create_dir_all(output_dir).map_err(|e| {
+        format!(
+            "Failed to create output folder {}: {e}",
+            output_dir.to_string_lossy()
+        )
     })
 }
@@ -0,0 +1,281 @@
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::error_protocol::error_output_exists;
+use crate::ffmpeg::temp_output_for;
+use crate::model::CollisionPolicy;
+
+const MAX_OUTPUT_NUMBER: u32 = 9_999;
+
+pub fn resolve_output_path(
+    input_path: &Path,
+    output_path: PathBuf,
+    policy: CollisionPolicy,
+) -> Result<PathBuf, String> {
+    refuse_input_as_output(input_path, &output_path)?;
+
+    match policy {
+        CollisionPolicy::Overwrite => Ok(output_path),
+        CollisionPolicy::Skip if is_taken(&output_path) => {
+            Err(error_output_exists(&output_path.to_string_lossy()))
+        }
+        CollisionPolicy::Skip => Ok(output_path),
+        CollisionPolicy::AutoNumber => output_candidates(&output_path)?
+            .find(|candidate| !is_taken(candidate))
+            .ok_or_else(|| no_free_name(&output_path)),
+    }
+}
+
+pub fn reserve_output_path(
+    input_path: &Path,
+    output_path: PathBuf,
+    policy: CollisionPolicy,
+    is_claimed: &dyn Fn(&Path) -> bool,
+) -> Result<PathBuf, String> {
+    refuse_input_as_output(input_path, &output_path)?;
+
+    let candidates: Box<dyn Iterator<Item = PathBuf>> = match policy {
+        CollisionPolicy::AutoNumber => Box::new(output_candidates(&output_path)?),
+        CollisionPolicy::Skip | CollisionPolicy::Overwrite => {
+            Box::new(std::iter::once(output_path.clone()))
+        }
+    };
+    for candidate in candidates {
+        if policy != CollisionPolicy::Overwrite && candidate.exists() {
+            if policy == CollisionPolicy::Skip {
+                return Err(error_output_exists(&candidate.to_string_lossy()));
+            }
+            continue;
+        }
+        let temp_output_path = temp_output_for(&candidate)?;
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_output_path)
+        {
+            Ok(_) => return Ok(candidate),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => match policy {
+                CollisionPolicy::AutoNumber => continue,
+                CollisionPolicy::Skip => {
+                    return Err(error_output_exists(&candidate.to_string_lossy()))
+                }
+                CollisionPolicy::Overwrite if is_claimed(&temp_output_path) => {
+                    return Err(format!(
+                        "Another conversion is already writing {}.",
+                        candidate.to_string_lossy()
+                    ))
+                }
+                CollisionPolicy::Overwrite => {
+                    return replace_stale_partial(&temp_output_path).map(|_| candidate)
+                }
+            },
+            Err(e) => {
+                return Err(format!(
+                    "Failed to reserve output {}: {e}",
+                    temp_output_path.to_string_lossy()
+                ))
+            }
+        }
+    }
+    Err(no_free_name(&output_path))
+}
+
+fn replace_stale_partial(temp_output_path: &Path) -> Result<(), String> {
+    OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(temp_output_path)
+        .map(|_| ())
+        .map_err(|e| {
+            format!(
+                "Failed to replace stale partial output {}: {e}",
+                temp_output_path.to_string_lossy()
+            )
+        })
+}
+
+fn output_candidates(output_path: &Path) -> Result<impl Iterator<Item = PathBuf>, String> {
+    let stem = output_path
+        .file_stem()
+        .ok_or_else(|| "Output path has no file name.".to_string())?
+        .to_string_lossy()
+        .to_string();
+    let extension = output_path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let numbered = output_path.to_path_buf();
+    Ok(std::iter::once(output_path.to_path_buf()).chain(
+        (2..=MAX_OUTPUT_NUMBER)
+            .map(move |number| numbered.with_file_name(format!("{stem} ({number}){extension}"))),
+    ))
+}
+
+fn no_free_name(output_path: &Path) -> String {
+    format!(
+        "No free output name left for {}.",
+        output_path.to_string_lossy()
+    )
+}
+
+fn refuse_input_as_output(input_path: &Path, output_path: &Path) -> Result<(), String> {
+    if is_same_file(input_path, output_path) {
+        return Err(format!(
+            "Output path is the same as the input: {}",
+            output_path.to_string_lossy()
+        ));
+    }
+    Ok(())
+}
+
+fn is_taken(output_path: &Path) -> bool {
+    output_path.exists()
+        || temp_output_for(output_path)
+            .map(|temp_output_path| temp_output_path.exists())
+            .unwrap_or(false)
+}
+
+fn is_same_file(input_path: &Path, output_path: &Path) -> bool {
+    match (fs::canonicalize(input_path), fs::canonicalize(output_path)) {
+        (Ok(input_path), Ok(output_path)) => input_path == output_path,
+        _ => input_path == output_path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::{reserve_output_path, resolve_output_path};
+    use crate::error_protocol::{is_error_code, ERROR_CODE_OUTPUT_EXISTS};
+    use crate::model::CollisionPolicy;
+    use crate::test_fs::scratch_dir;
+
+    fn touch(path: &Path) {
+        fs::write(path, b"").expect("file should be written");
+    }
+
+    #[test]
+    fn auto_number_skips_existing_and_partial_outputs() {
+        let dir = scratch_dir("output-path-auto");
+        let output = dir.join("clip.av1.mp4");
+        touch(&output);
+        touch(&dir.join("clip.av1 (2).partial.mp4"));
+
+        let resolved =
+            resolve_output_path(&dir.join("clip.mkv"), output, CollisionPolicy::AutoNumber);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            resolved.expect("a free name should exist"),
+            dir.join("clip.av1 (3).mp4")
+        );
+    }
+
+    #[test]
+    fn skip_reports_existing_output() {
+        let dir = scratch_dir("output-path-skip");
+        let output = dir.join("clip.av1.mp4");
+        touch(&output);
+
+        let skipped =
+            resolve_output_path(&dir.join("clip.mkv"), output.clone(), CollisionPolicy::Skip);
+        let overwritten = resolve_output_path(
+            &dir.join("clip.mkv"),
+            output.clone(),
+            CollisionPolicy::Overwrite,
+        );
+        let _ = fs::remove_dir_all(&dir);
+
+        let error = skipped.expect_err("existing output should be skipped");
+        assert!(is_error_code(&error, ERROR_CODE_OUTPUT_EXISTS));
+        assert_eq!(overwritten.expect("overwrite should keep the path"), output);
+    }
+
+    #[test]
+    fn reserving_two_jobs_with_the_same_stem_gives_distinct_outputs() {
+        let dir = scratch_dir("output-path-reserve");
+        let output = dir.join("clip.av1.mp4");
+
+        let first = reserve_output_path(
+            &dir.join("clip.mkv"),
+            output.clone(),
+            CollisionPolicy::AutoNumber,
+            &|_| true,
+        );
+        let second = reserve_output_path(
+            &dir.join("clip.mov"),
+            output.clone(),
+            CollisionPolicy::AutoNumber,
+            &|_| true,
+        );
+        let skipped = reserve_output_path(
+            &dir.join("clip.avi"),
+            output,
+            CollisionPolicy::Skip,
+            &|_| true,
+        );
+        let reserved = (
+            dir.join("clip.av1.partial.mp4").exists(),
+            dir.join("clip.av1 (2).partial.mp4").exists(),
+        );
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            first.expect("first job should reserve"),
+            dir.join("clip.av1.mp4")
+        );
+        assert_eq!(
+            second.expect("second job should reserve"),
+            dir.join("clip.av1 (2).mp4")
+        );
+        assert!(is_error_code(
+            &skipped.expect_err("reserved name should be skipped"),
+            ERROR_CODE_OUTPUT_EXISTS
+        ));
+        assert_eq!(reserved, (true, true));
+    }
+
+    #[test]
+    fn overwrite_replaces_a_stale_partial_but_not_a_claimed_one() {
+        let dir = scratch_dir("output-path-stale");
+        let output = dir.join("clip.av1.mp4");
+        let partial = dir.join("clip.av1.partial.mp4");
+        fs::write(&partial, b"left over").expect("file should be written");
+
+        let claimed = reserve_output_path(
+            &dir.join("clip.mkv"),
+            output.clone(),
+            CollisionPolicy::Overwrite,
+            &|path| path == partial,
+        );
+        let stale = reserve_output_path(
+            &dir.join("clip.mkv"),
+            output.clone(),
+            CollisionPolicy::Overwrite,
+            &|_| false,
+        );
+        let partial_len = fs::metadata(&partial).map(|metadata| metadata.len());
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(claimed
+            .expect_err("a running job's partial should be kept")
+            .contains("already writing"));
+        assert_eq!(stale.expect("a stale partial should be replaced"), output);
+        assert_eq!(partial_len.ok(), Some(0));
+    }
+
+    #[test]
+    fn output_equal_to_input_is_refused() {
+        let dir = scratch_dir("output-path-same");
+        let input = dir.join("clip.mp4");
+        touch(&input);
+
+        let result = resolve_output_path(&input, input.clone(), CollisionPolicy::Overwrite);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,332 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{stem}.av1";
+pub const OUTPUT_EXTENSION: &str = ".mp4";
+const ILLEGAL_FILE_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+const MAX_FILE_NAME_LEN: usize = 255;
+const MAX_PATH_LEN: usize = 259;
+const RESERVED_SUFFIX_LEN: usize = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Stem,
+    Ext,
+    Encoder,
+    Height,
+    Date,
+    Ratio,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TemplateValues {
+    pub stem: String,
+    pub ext: String,
+    pub height: Option<u32>,
+    pub date: String,
+    pub ratio_percent: Option<f64>,
+}
+
+pub struct OutputNaming {
+    pub dir: PathBuf,
+    pub template: OutputTemplate,
+    pub values: TemplateValues,
+}
+
+impl OutputTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err(format!(
+                    "Output name template has an unmatched '}}': {template}"
+                ));
+            }
+            if open > 0 {
+                segments.push(Segment::Literal(rest[..open].to_string()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .map(|offset| open + offset)
+                .ok_or_else(|| format!("Output name template has an unclosed '{{': {template}"))?;
+            segments.push(Segment::Field(parse_field(&rest[open + 1..close])?));
+            rest = &rest[close + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        if !segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Field(_)))
+        {
+            return Err(format!(
+                "Output name template needs at least one placeholder, or every file gets the same name: {template}"
+            ));
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn uses_encoder(&self) -> bool {
+        self.segments.contains(&Segment::Field(Field::Encoder))
+    }
+
+    pub fn render(&self, values: &TemplateValues, encoder: &str) -> Result<String, String> {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Field(Field::Stem) => rendered.push_str(&values.stem),
+                Segment::Field(Field::Ext) => rendered.push_str(&values.ext),
+                Segment::Field(Field::Encoder) => rendered.push_str(encoder),
+                Segment::Field(Field::Height) => {
+                    let height = values.height.ok_or_else(|| {
+                        "The {height} placeholder needs a video stream with a known height."
+                            .to_string()
+                    })?;
+                    rendered.push_str(&height.to_string());
+                }
+                Segment::Field(Field::Date) => rendered.push_str(&values.date),
+                Segment::Field(Field::Ratio) => {
+                    let ratio_percent = values.ratio_percent.ok_or_else(|| {
+                        "The {ratio} placeholder needs a size target, not a quality level."
+                            .to_string()
+                    })?;
+                    rendered.push_str(&format!("{ratio_percent:.0}"));
+                }
+            }
+        }
+        Ok(rendered)
+    }
+}
+
+impl OutputNaming {
+    pub fn output_path(&self, encoder: &str) -> Result<PathBuf, String> {
+        let stem = sanitize_file_stem(&self.template.render(&self.values, encoder)?);
+        let stem = truncate_stem(&stem, stem_budget(&self.dir)?);
+        if stem.is_empty() {
+            return Err("Output name template produced an empty file name.".to_string());
+        }
+        Ok(self.dir.join(format!("{stem}{OUTPUT_EXTENSION}")))
+    }
+}
+
+fn parse_field(name: &str) -> Result<Field, String> {
+    match name {
+        "stem" => Ok(Field::Stem),
+        "ext" => Ok(Field::Ext),
+        "encoder" => Ok(Field::Encoder),
+        "height" => Ok(Field::Height),
+        "date" => Ok(Field::Date),
+        "ratio" => Ok(Field::Ratio),
+        _ => Err(format!(
+            "Unknown output name placeholder {{{name}}}. Use {{stem}}, {{ext}}, {{encoder}}, {{height}}, {{date}} or {{ratio}}."
+        )),
+    }
+}
+
+pub fn sanitize_file_stem(raw: &str) -> String {
+    let replaced: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_control() || ILLEGAL_FILE_NAME_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let trimmed = trim_file_stem(&replaced);
+
+    let device_name = trimmed.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_FILE_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(device_name))
+    {
+        return format!("_{trimmed}");
+    }
+    trimmed.to_string()
+}
+
+fn trim_file_stem(stem: &str) -> &str {
+    stem.trim_start().trim_end_matches([' ', '.'])
+}
+
+fn stem_budget(dir: &Path) -> Result<usize, String> {
+    let dir_len = dir.to_string_lossy().encode_utf16().count() + 1;
+    let budget = MAX_FILE_NAME_LEN
+        .min(MAX_PATH_LEN.saturating_sub(dir_len))
+        .saturating_sub(RESERVED_SUFFIX_LEN);
+    if budget == 0 {
+        return Err(format!(
+            "Output folder path is too long: {}",
+            dir.to_string_lossy()
+        ));
+    }
+    Ok(budget)
+}
+
+pub fn truncate_stem(stem: &str, budget: usize) -> String {
+    let mut utf16_len = 0;
+    let mut end = 0;
+    for (index, c) in stem.char_indices() {
+        utf16_len += c.len_utf16();
+        if utf16_len > budget || index + c.len_utf8() > budget {
+            break;
+        }
+        end = index + c.len_utf8();
+    }
+    trim_file_stem(&stem[..end]).to_string()
+}
+
+pub fn today_utc() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or_default();
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{
+        civil_from_days, sanitize_file_stem, truncate_stem, OutputNaming, OutputTemplate,
+        TemplateValues, DEFAULT_OUTPUT_TEMPLATE,
+    };
+
+    fn values(stem: &str) -> TemplateValues {
+        TemplateValues {
+            stem: stem.to_string(),
+            ext: "mkv".to_string(),
+            height: Some(1080),
+            date: "2026-10-18".to_string(),
+            ratio_percent: Some(50.0),
+        }
+    }
+
+    fn naming(template: &str, stem: &str) -> OutputNaming {
+        OutputNaming {
+            dir: PathBuf::from("out"),
+            template: OutputTemplate::parse(template).expect("template should parse"),
+            values: values(stem),
+        }
+    }
+
+    #[test]
+    fn default_template_matches_legacy_name() {
+        let path = naming(DEFAULT_OUTPUT_TEMPLATE, "input")
+            .output_path("libsvtav1")
+            .expect("default template should render");
+        assert_eq!(path, PathBuf::from("out").join("input.av1.mp4"));
+    }
+
+    #[test]
+    fn template_renders_every_placeholder() {
+        let path = naming("{stem}-{ext}-{encoder}-{height}p-{date}-{ratio}", "clip")
+            .output_path("av1_nvenc")
+            .expect("template should render");
+        assert_eq!(
+            path.file_name().and_then(|name| name.to_str()),
+            Some("clip-mkv-av1_nvenc-1080p-2026-10-18-50.mp4")
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_templates() {
+        assert!(OutputTemplate::parse("{stem").is_err());
+        assert!(OutputTemplate::parse("stem}").is_err());
+        assert!(OutputTemplate::parse("{size}").is_err());
+        assert!(OutputTemplate::parse("fixed-name").is_err());
+    }
+
+    #[test]
+    fn ratio_placeholder_needs_size_target() {
+        let mut naming = naming("{stem}-{ratio}", "clip");
+        naming.values.ratio_percent = None;
+        assert!(naming.output_path("libsvtav1").is_err());
+    }
+
+    #[test]
+    fn sanitize_replaces_illegal_characters_and_reserved_names() {
+        assert_eq!(
+            sanitize_file_stem("a<b>:c\"d|e?f*g/h\\i"),
+            "a_b__c_d_e_f_g_h_i"
+        );
+        assert_eq!(sanitize_file_stem("tab\there\u{7}"), "tab_here_");
+        assert_eq!(sanitize_file_stem("  trailing dots... "), "trailing dots");
+        assert_eq!(sanitize_file_stem("con.av1"), "_con.av1");
+        assert_eq!(sanitize_file_stem("console.av1"), "console.av1");
+    }
+
+    #[test]
+    fn sanitize_keeps_unicode_names() {
+        let stem = "Café — 東京の夜 🎬 שלום e\u{301}";
+        assert_eq!(sanitize_file_stem(stem), stem);
+    }
+
+    #[test]
+    fn truncate_respects_utf16_and_byte_limits() {
+        assert_eq!(truncate_stem("🎬🎬🎬", 5), "🎬");
+        assert_eq!(truncate_stem("東京東京", 7), "東京");
+        assert_eq!(truncate_stem("ab. cd", 4), "ab");
+    }
+
+    #[test]
+    fn long_unicode_names_fit_path_limits() {
+        let long_stem = "夜".repeat(400);
+        let path = naming(DEFAULT_OUTPUT_TEMPLATE, &long_stem)
+            .output_path("libsvtav1")
+            .expect("long name should be truncated");
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .expect("file name should be valid UTF-8");
+
+        assert!(file_name.len() <= 255 - 15);
+        assert!(file_name.ends_with(".mp4"));
+        assert!(file_name.starts_with('夜'));
+    }
+
+    #[test]
+    fn civil_from_days_handles_epoch_and_leap_day() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+}
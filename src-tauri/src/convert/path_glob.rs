@@ -0,0 +1,79 @@
+pub fn glob_matches(pattern: &str, relative_path: &str) -> bool {
+    let pattern = normalize(pattern);
+    let relative_path = normalize(relative_path);
+    let subject = if pattern.contains(&'/') {
+        &relative_path[..]
+    } else {
+        let name_start = relative_path
+            .iter()
+            .rposition(|c| *c == '/')
+            .map_or(0, |index| index + 1);
+        &relative_path[name_start..]
+    };
+    matches_from(&pattern, subject)
+}
+
+fn normalize(text: &str) -> Vec<char> {
+    text.trim_start_matches("./")
+        .chars()
+        .map(|c| {
+            if c == '\\' {
+                '/'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
+
+fn matches_from(pattern: &[char], subject: &[char]) -> bool {
+    match pattern {
+        [] => subject.is_empty(),
+        ['*', '*', rest @ ..] => {
+            if let ['/', after_slash @ ..] = rest {
+                if matches_from(after_slash, subject) {
+                    return true;
+                }
+            }
+            (0..=subject.len()).any(|start| matches_from(rest, &subject[start..]))
+        }
+        ['*', rest @ ..] => (0..=subject.len())
+            .take_while(|&end| end == 0 || subject[end - 1] != '/')
+            .any(|start| matches_from(rest, &subject[start..])),
+        ['?', rest @ ..] => match subject {
+            [c, subject_rest @ ..] if *c != '/' => matches_from(rest, subject_rest),
+            _ => false,
+        },
+        [expected, rest @ ..] => match subject {
+            [c, subject_rest @ ..] if c == expected => matches_from(rest, subject_rest),
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_matches;
+
+    #[test]
+    fn pattern_without_slash_matches_file_name() {
+        assert!(glob_matches("*.MKV", "season 1/episode 01.mkv"));
+        assert!(glob_matches("clip-??.mp4", "clip-01.mp4"));
+        assert!(!glob_matches("clip-??.mp4", "clip-001.mp4"));
+    }
+
+    #[test]
+    fn single_star_stays_within_one_folder() {
+        assert!(glob_matches("raw/*.mov", "raw/take.mov"));
+        assert!(!glob_matches("raw/*.mov", "raw/day 2/take.mov"));
+    }
+
+    #[test]
+    fn double_star_crosses_folders() {
+        assert!(glob_matches("**/proxies/**", "2024/trip/proxies/clip.mp4"));
+        assert!(glob_matches("**/proxies/**", "proxies/clip.mp4"));
+        assert!(glob_matches("raw/**/*.mov", "raw/take.mov"));
+        assert!(glob_matches("raw\\**\\*.mov", "raw\\day 2\\take.mov"));
+        assert!(!glob_matches("**/proxies/**", "2024/proxy/clip.mp4"));
+    }
+}
@@ -1,38 +1,224 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tauri::AppHandle;
 
-use crate::encoder_service::get_available_av1_encoders;
+use crate::encoder_service::{get_av1_encoder_descriptions, get_available_av1_encoders};
+use crate::error_protocol::{is_error_code, ERROR_CODE_OUTPUT_EXISTS};
 use crate::ffmpeg::{
-    default_output_for_input, read_probe_metadata, resolve_encoder_candidates, resolve_tool_path,
-    video_rate_args,
+    output_marker_args, probe_media_info, resolve_encoder_candidates, resolve_tool_path,
+    supports_constant_quality, supports_two_pass, temp_output_for, video_preset_args,
+    video_quality_args, video_rate_args, vmaf_filter_graph, EncoderDescription, MediaInfo,
+    MAX_PRESET_LEVEL, MAX_QUALITY_LEVEL, MIN_PRESET_LEVEL,
 };
-use crate::model::ConvertRequest;
+use crate::model::{CollisionPolicy, ConvertRequest, JobId, TargetPolicy};
 
-use super::cancellation::abort_if_cancel_requested;
+use super::cancellation::abort_if_stop_requested;
+use super::container_overhead::estimate_container_overhead_bytes;
+use super::output_path::{reserve_output_path, resolve_output_path};
+use super::output_template::{
+    today_utc, OutputNaming, OutputTemplate, TemplateValues, DEFAULT_OUTPUT_TEMPLATE,
+};
+use super::source_tree::mirrored_output_dir;
+use super::stream_selection::{select_output_streams, OutputStreams};
 
-const AUDIO_BITRATE_FALLBACK_KBPS: u32 = 128;
 const STRICT_SIZE_DEFAULT: bool = true;
 const MIN_VIDEO_BITRATE_KBPS: i64 = 100;
+const BYTES_PER_MEGABYTE: f64 = 1024.0 * 1024.0;
+const DEFAULT_MAX_ENCODE_ATTEMPTS: u32 = 3;
+const MAX_ENCODE_ATTEMPTS_LIMIT: u32 = 5;
+const BITRATE_CORRECTION_MARGIN: f64 = 0.98;
+const VMAF_SEARCH_INITIAL_LEVEL: u32 = 32;
+const MAX_VMAF_SCORE: f64 = 100.0;
 
 pub struct ConversionPlan {
     pub ffmpeg_path: PathBuf,
+    pub ffprobe_path: PathBuf,
     pub output_path: PathBuf,
+    pub temp_output_path: PathBuf,
     pub duration_sec: f64,
-    pub target_size_bytes: u64,
-    pub audio_bitrate_kbps: u32,
-    pub video_bitrate_kbps: u32,
+    pub rate_control: RateControl,
+    pub output_streams: OutputStreams,
     pub encoder_candidates: Vec<String>,
+    pub encoder_descriptions: HashMap<String, EncoderDescription>,
+    pub two_pass: bool,
+    pub max_encode_attempts: u32,
+    pub vmaf_target: Option<f64>,
+    pub preset_level: Option<u32>,
+    pub job_id: Option<JobId>,
+    pub output_naming: OutputNaming,
+    pub collision_policy: CollisionPolicy,
     input_path: String,
 }
 
+#[derive(Clone, Copy)]
+pub enum RateControl {
+    TargetSize {
+        target_policy: TargetPolicy,
+        target_size_bytes: u64,
+        video_bitrate_kbps: u32,
+    },
+    ConstantQuality {
+        quality_level: u32,
+    },
+}
+
+impl ConversionPlan {
+    pub fn input_path(&self) -> &str {
+        &self.input_path
+    }
+
+    pub fn uses_two_pass(&self, encoder: &str) -> bool {
+        self.two_pass && supports_two_pass(encoder)
+    }
+
+    pub fn encoder_description(&self, encoder: &str) -> Option<&EncoderDescription> {
+        self.encoder_descriptions.get(encoder)
+    }
+
+    fn preset_args(&self, encoder: &str) -> Vec<String> {
+        self.preset_level
+            .map(|level| video_preset_args(level, encoder, self.encoder_description(encoder)))
+            .unwrap_or_default()
+    }
+
+    pub fn target_policy(&self) -> Option<TargetPolicy> {
+        match self.rate_control {
+            RateControl::TargetSize { target_policy, .. } => Some(target_policy),
+            RateControl::ConstantQuality { .. } => None,
+        }
+    }
+
+    pub fn target_size_bytes(&self) -> Option<u64> {
+        match self.rate_control {
+            RateControl::TargetSize {
+                target_size_bytes, ..
+            } => Some(target_size_bytes),
+            RateControl::ConstantQuality { .. } => None,
+        }
+    }
+
+    pub fn video_bitrate_kbps(&self) -> Option<u32> {
+        match self.rate_control {
+            RateControl::TargetSize {
+                video_bitrate_kbps, ..
+            } => Some(video_bitrate_kbps),
+            RateControl::ConstantQuality { .. } => None,
+        }
+    }
+
+    pub fn quality_level(&self) -> Option<u32> {
+        match self.rate_control {
+            RateControl::TargetSize { .. } => None,
+            RateControl::ConstantQuality { quality_level } => Some(quality_level),
+        }
+    }
+
+    pub fn fixed_output_bytes(&self) -> u64 {
+        let non_video_bytes =
+            self.output_streams.non_video_bitrate_kbps() as f64 * 1000.0 * self.duration_sec / 8.0;
+        non_video_bytes.floor() as u64
+            + estimate_container_overhead_bytes(
+                self.output_streams.track_count(),
+                self.output_streams.packet_count(),
+            )
+    }
+
+    pub fn set_quality_level(&mut self, level: u32) {
+        if let RateControl::ConstantQuality { quality_level } = &mut self.rate_control {
+            *quality_level = level;
+        }
+    }
+
+    pub fn reserve_output(
+        &mut self,
+        is_claimed: &dyn Fn(&Path) -> bool,
+    ) -> Result<PathBuf, String> {
+        let first_encoder = self
+            .encoder_candidates
+            .first()
+            .ok_or_else(|| "No AV1 encoder candidates to plan with.".to_string())?;
+        self.output_path = reserve_output_path(
+            Path::new(&self.input_path),
+            self.output_naming.output_path(first_encoder)?,
+            self.collision_policy,
+            is_claimed,
+        )?;
+        self.temp_output_path = temp_output_for(&self.output_path)?;
+        Ok(self.temp_output_path.clone())
+    }
+
+    pub fn retarget_output(
+        &mut self,
+        encoder: &str,
+        is_claimed: &dyn Fn(&Path) -> bool,
+    ) -> Result<Option<PathBuf>, String> {
+        if !self.output_naming.template.uses_encoder() {
+            return Ok(None);
+        }
+        match reserve_output_path(
+            Path::new(&self.input_path),
+            self.output_naming.output_path(encoder)?,
+            self.collision_policy,
+            is_claimed,
+        ) {
+            Ok(output_path) => {
+                self.output_path = output_path;
+                temp_output_for(&self.output_path).map(Some)
+            }
+            Err(error) if is_error_code(&error, ERROR_CODE_OUTPUT_EXISTS) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn set_video_bitrate_kbps(&mut self, kbps: u32) {
+        if let RateControl::TargetSize {
+            video_bitrate_kbps, ..
+        } = &mut self.rate_control
+        {
+            *video_bitrate_kbps = kbps;
+        }
+    }
+}
+
+pub struct EncodeStep {
+    pub label: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+pub enum EncodePass<'a> {
+    Single,
+    Analysis { log_prefix: &'a Path },
+    Final { log_prefix: &'a Path },
+}
+
 pub fn build_conversion_plan(
     app: &AppHandle,
     request: &ConvertRequest,
+    job_id: JobId,
 ) -> Result<ConversionPlan, String> {
-    abort_if_cancel_requested(app)?;
+    build_plan(app, request, Some(job_id))
+}
 
+pub fn build_probe_plan(
+    app: &AppHandle,
+    request: &ConvertRequest,
+) -> Result<ConversionPlan, String> {
+    build_plan(app, request, None)
+}
+
+fn build_plan(
+    app: &AppHandle,
+    request: &ConvertRequest,
+    job_id: Option<JobId>,
+) -> Result<ConversionPlan, String> {
+    abort_if_stop_requested(app, job_id)?;
+
+    let max_encode_attempts = resolve_max_encode_attempts(request.max_encode_attempts)?;
+    let vmaf_target = resolve_vmaf_target(request)?;
+    let preset_level = resolve_preset_level(request.preset_level)?;
     let input_path = PathBuf::from(&request.input_path);
     if !input_path.exists() {
         return Err(format!(
@@ -41,86 +227,707 @@ pub fn build_conversion_plan(
         ));
     }
 
-    let output_path = PathBuf::from(default_output_for_input(&request.input_path)?);
+    let output_template = OutputTemplate::parse(
+        request
+            .output_template
+            .as_deref()
+            .unwrap_or(DEFAULT_OUTPUT_TEMPLATE),
+    )?;
+    let output_dir = resolve_output_dir(request, &input_path)?;
     let ffmpeg_path = resolve_tool_path(app, "ffmpeg.exe")?;
     let ffprobe_path = resolve_tool_path(app, "ffprobe.exe")?;
 
-    abort_if_cancel_requested(app)?;
-    let available_all = get_available_av1_encoders(app)?;
-    let encoder_candidates =
+    abort_if_stop_requested(app, job_id)?;
+    let available_all = get_available_av1_encoders(app, request.av1_encoder.as_deref())?;
+    let encoder_descriptions = get_av1_encoder_descriptions(app)?;
+    let mut encoder_candidates =
         resolve_encoder_candidates(request.av1_encoder.as_deref(), &available_all)?;
+    if request.quality_level.is_some() || vmaf_target.is_some() {
+        encoder_candidates = retain_constant_quality_encoders(
+            encoder_candidates,
+            request.av1_encoder.as_deref(),
+            &encoder_descriptions,
+        )?;
+    }
 
     let input_size = fs::metadata(&input_path)
         .map_err(|e| format!("Could not read input file metadata: {e}"))?
         .len();
-    let target_size_bytes = input_size / 2;
 
-    let probe_metadata = read_probe_metadata(
-        &ffprobe_path,
-        &request.input_path,
-        AUDIO_BITRATE_FALLBACK_KBPS,
-    )?;
-    abort_if_cancel_requested(app)?;
+    let media = probe_media_info(&ffprobe_path, &request.input_path)?;
+    abort_if_stop_requested(app, job_id)?;
 
-    let duration_sec = probe_metadata.duration_sec;
-    let audio_bitrate_kbps = probe_metadata.audio_bitrate_kbps;
+    let duration_sec = media.duration_sec;
+    let output_streams = select_output_streams(&media)?;
 
-    let video_bitrate_kbps =
-        compute_video_bitrate_kbps(target_size_bytes, duration_sec, audio_bitrate_kbps)?;
+    let rate_control = match request.quality_level {
+        None if vmaf_target.is_some() => RateControl::ConstantQuality {
+            quality_level: VMAF_SEARCH_INITIAL_LEVEL,
+        },
+        Some(quality_level) => {
+            if quality_level > MAX_QUALITY_LEVEL {
+                return Err(format!(
+                    "Quality level must be between 0 and {MAX_QUALITY_LEVEL} (got {quality_level})."
+                ));
+            }
+            RateControl::ConstantQuality { quality_level }
+        }
+        None => {
+            let target_policy = request.target_policy;
+            let target_size_bytes =
+                resolve_target_size_bytes(&target_policy, input_size, duration_sec)?;
+            let overhead_bytes = estimate_container_overhead_bytes(
+                output_streams.track_count(),
+                output_streams.packet_count(),
+            );
+            let video_bitrate_kbps = compute_video_bitrate_kbps(
+                target_size_bytes.saturating_sub(overhead_bytes),
+                duration_sec,
+                output_streams.non_video_bitrate_kbps(),
+            )
+            .map_err(|error| format!("Target {target_policy} cannot be met: {error}"))?;
+            RateControl::TargetSize {
+                target_policy,
+                target_size_bytes,
+                video_bitrate_kbps,
+            }
+        }
+    };
+    let two_pass = request.two_pass && matches!(rate_control, RateControl::TargetSize { .. });
+
+    let output_naming = OutputNaming {
+        dir: output_dir,
+        template: output_template,
+        values: template_values(&input_path, &media, &rate_control, input_size),
+    };
+    let first_encoder = encoder_candidates
+        .first()
+        .ok_or_else(|| "No AV1 encoder candidates to plan with.".to_string())?;
+    let output_path = resolve_output_path(
+        &input_path,
+        output_naming.output_path(first_encoder)?,
+        request.collision_policy,
+    )?;
+    let temp_output_path = temp_output_for(&output_path)?;
 
     Ok(ConversionPlan {
         ffmpeg_path,
+        ffprobe_path,
         output_path,
+        temp_output_path,
         duration_sec,
-        target_size_bytes,
-        audio_bitrate_kbps,
-        video_bitrate_kbps,
+        rate_control,
+        output_streams,
         encoder_candidates,
+        encoder_descriptions,
+        two_pass,
+        max_encode_attempts,
+        vmaf_target,
+        preset_level,
+        job_id,
+        output_naming,
+        collision_policy: request.collision_policy,
         input_path: request.input_path.clone(),
     })
 }
 
-pub fn build_encode_args(plan: &ConversionPlan, encoder: &str) -> Vec<String> {
-    let mut args = vec![
-        "-y".to_string(),
-        "-i".to_string(),
-        plan.input_path.clone(),
-        "-c:v".to_string(),
-        encoder.to_string(),
-    ];
+fn resolve_output_dir(request: &ConvertRequest, input_path: &Path) -> Result<PathBuf, String> {
+    let Some(output_dir) = request.output_dir.as_deref() else {
+        if request.source_root.is_some() {
+            return Err("Mirroring a source folder needs an output folder.".to_string());
+        }
+        return input_path
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| "Input path has no parent folder.".to_string());
+    };
 
-    args.extend(video_rate_args(
-        plan.video_bitrate_kbps,
-        STRICT_SIZE_DEFAULT,
-        encoder,
-    ));
+    let output_dir = PathBuf::from(output_dir);
+    if output_dir.exists() && !output_dir.is_dir() {
+        return Err(format!(
+            "Output folder is not a folder: {}",
+            output_dir.to_string_lossy()
+        ));
+    }
+    match request.source_root.as_deref() {
+        Some(source_root) => mirrored_output_dir(Path::new(source_root), input_path, &output_dir),
+        None => Ok(output_dir),
+    }
+}
+
+fn template_values(
+    input_path: &Path,
+    media: &MediaInfo,
+    rate_control: &RateControl,
+    input_size: u64,
+) -> TemplateValues {
+    let ratio_percent = match rate_control {
+        RateControl::TargetSize {
+            target_size_bytes, ..
+        } if input_size > 0 => Some(*target_size_bytes as f64 / input_size as f64 * 100.0),
+        _ => None,
+    };
+    TemplateValues {
+        stem: input_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        ext: input_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        height: media
+            .primary_video()
+            .and_then(|stream| stream.video.as_ref())
+            .map(|video| video.height),
+        date: today_utc(),
+        ratio_percent,
+    }
+}
+
+pub fn single_pass_step(plan: &ConversionPlan, encoder: &str, label_suffix: &str) -> EncodeStep {
+    EncodeStep {
+        label: format!("Encode{label_suffix}"),
+        args: build_encode_args(plan, encoder, EncodePass::Single),
+    }
+}
+
+pub fn build_encode_args(plan: &ConversionPlan, encoder: &str, pass: EncodePass) -> Vec<String> {
+    let mut args = vec!["-y".to_string(), "-i".to_string(), plan.input_path.clone()];
+    if matches!(pass, EncodePass::Analysis { .. }) {
+        args.extend(plan.output_streams.video_map_args());
+    } else {
+        args.extend(plan.output_streams.map_args());
+    }
+    args.extend(vec!["-c:v".to_string(), encoder.to_string()]);
+    args.extend(plan.preset_args(encoder));
+
+    match plan.rate_control {
+        RateControl::TargetSize {
+            video_bitrate_kbps, ..
+        } => {
+            let strict_size = STRICT_SIZE_DEFAULT && matches!(pass, EncodePass::Single);
+            args.extend(video_rate_args(
+                video_bitrate_kbps,
+                strict_size,
+                encoder,
+                plan.encoder_description(encoder),
+            ));
+        }
+        RateControl::ConstantQuality { quality_level } => {
+            args.extend(video_quality_args(
+                quality_level,
+                encoder,
+                plan.encoder_description(encoder),
+            ));
+        }
+    }
+
+    match pass {
+        EncodePass::Single => {}
+        EncodePass::Analysis { log_prefix } => {
+            args.extend(pass_args(1, log_prefix));
+            args.extend(vec![
+                "-an".to_string(),
+                "-f".to_string(),
+                "null".to_string(),
+                "-".to_string(),
+            ]);
+            return args;
+        }
+        EncodePass::Final { log_prefix } => args.extend(pass_args(2, log_prefix)),
+    }
+
+    args.extend(plan.output_streams.codec_args());
+    args.extend(output_marker_args());
     args.extend(vec![
-        "-c:a".to_string(),
-        "aac".to_string(),
-        "-b:a".to_string(),
-        format!("{}k", plan.audio_bitrate_kbps),
         "-movflags".to_string(),
         "+faststart".to_string(),
-        plan.output_path.to_string_lossy().to_string(),
+        plan.temp_output_path.to_string_lossy().to_string(),
     ]);
 
     args
 }
 
+pub fn build_sample_encode_args(
+    plan: &ConversionPlan,
+    encoder: &str,
+    quality_level: u32,
+    start_sec: f64,
+    duration_sec: f64,
+    sample_path: &Path,
+) -> Vec<String> {
+    let mut args = vec!["-y".to_string()];
+    args.extend(sample_window_args(plan, start_sec, duration_sec));
+    args.extend(plan.output_streams.video_map_args());
+    args.extend(vec!["-c:v".to_string(), encoder.to_string()]);
+    args.extend(plan.preset_args(encoder));
+    args.extend(video_quality_args(
+        quality_level,
+        encoder,
+        plan.encoder_description(encoder),
+    ));
+    args.extend(vec![
+        "-an".to_string(),
+        sample_path.to_string_lossy().to_string(),
+    ]);
+    args
+}
+
+pub fn build_vmaf_score_args(
+    plan: &ConversionPlan,
+    start_sec: f64,
+    duration_sec: f64,
+    sample_path: &Path,
+    log_path: &Path,
+) -> Vec<String> {
+    let mut args = vec!["-i".to_string(), sample_path.to_string_lossy().to_string()];
+    args.extend(sample_window_args(plan, start_sec, duration_sec));
+    args.extend(vec![
+        "-lavfi".to_string(),
+        vmaf_filter_graph(plan.output_streams.video_index, log_path),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ]);
+    args
+}
+
+fn sample_window_args(plan: &ConversionPlan, start_sec: f64, duration_sec: f64) -> Vec<String> {
+    vec![
+        "-ss".to_string(),
+        format!("{start_sec:.3}"),
+        "-t".to_string(),
+        format!("{duration_sec:.3}"),
+        "-i".to_string(),
+        plan.input_path.clone(),
+    ]
+}
+
+fn pass_args(pass_number: u8, log_prefix: &Path) -> Vec<String> {
+    vec![
+        "-pass".to_string(),
+        pass_number.to_string(),
+        "-passlogfile".to_string(),
+        log_prefix.to_string_lossy().to_string(),
+    ]
+}
+
+pub fn correct_video_bitrate_kbps(
+    current_video_kbps: u32,
+    target_size_bytes: u64,
+    output_size_bytes: u64,
+    fixed_bytes: u64,
+) -> Result<u32, String> {
+    if output_size_bytes == 0 {
+        return Err("Encoded output is empty.".to_string());
+    }
+    if target_size_bytes <= fixed_bytes || output_size_bytes <= fixed_bytes {
+        return Err(format!(
+            "Audio, subtitles and container overhead take an estimated {fixed_bytes} bytes, \
+             which leaves no room for video."
+        ));
+    }
+
+    let scale = (target_size_bytes - fixed_bytes) as f64 / (output_size_bytes - fixed_bytes) as f64;
+    let corrected_kbps = (current_video_kbps as f64 * scale * BITRATE_CORRECTION_MARGIN).floor();
+    if corrected_kbps < MIN_VIDEO_BITRATE_KBPS as f64 {
+        return Err(format!(
+            "Corrected video bitrate too low ({corrected_kbps} kbps, minimum {MIN_VIDEO_BITRATE_KBPS} kbps)."
+        ));
+    }
+
+    Ok(corrected_kbps as u32)
+}
+
+fn retain_constant_quality_encoders(
+    candidates: Vec<String>,
+    requested: Option<&str>,
+    descriptions: &HashMap<String, EncoderDescription>,
+) -> Result<Vec<String>, String> {
+    if let Some(requested_name) = requested {
+        if !supports_constant_quality(requested_name, descriptions.get(requested_name)) {
+            return Err(format!(
+                "Requested AV1 encoder does not support constant-quality mode: {requested_name}"
+            ));
+        }
+    }
+
+    let supported: Vec<String> = candidates
+        .into_iter()
+        .filter(|encoder| supports_constant_quality(encoder, descriptions.get(encoder)))
+        .collect();
+    if supported.is_empty() {
+        return Err("No available AV1 encoder supports constant-quality mode.".to_string());
+    }
+
+    Ok(supported)
+}
+
+fn resolve_vmaf_target(request: &ConvertRequest) -> Result<Option<f64>, String> {
+    let Some(target) = request.vmaf_target else {
+        return Ok(None);
+    };
+
+    if request.quality_level.is_some() {
+        return Err("Set either a quality level or a VMAF target, not both.".to_string());
+    }
+    if !(target > 0.0 && target <= MAX_VMAF_SCORE) {
+        return Err(format!(
+            "VMAF target must be between 0 and {MAX_VMAF_SCORE} (got {target})."
+        ));
+    }
+
+    Ok(Some(target))
+}
+
+fn resolve_max_encode_attempts(requested: Option<u32>) -> Result<u32, String> {
+    let attempts = requested.unwrap_or(DEFAULT_MAX_ENCODE_ATTEMPTS);
+    if !(1..=MAX_ENCODE_ATTEMPTS_LIMIT).contains(&attempts) {
+        return Err(format!(
+            "Max encode attempts must be between 1 and {MAX_ENCODE_ATTEMPTS_LIMIT} (got {attempts})."
+        ));
+    }
+
+    Ok(attempts)
+}
+
+fn resolve_preset_level(requested: Option<u32>) -> Result<Option<u32>, String> {
+    if let Some(level) = requested {
+        if !(MIN_PRESET_LEVEL..=MAX_PRESET_LEVEL).contains(&level) {
+            return Err(format!(
+                "Preset level must be between {MIN_PRESET_LEVEL} (fastest) and {MAX_PRESET_LEVEL} (best compression) (got {level})."
+            ));
+        }
+    }
+
+    Ok(requested)
+}
+
+fn resolve_target_size_bytes(
+    policy: &TargetPolicy,
+    input_size: u64,
+    duration_sec: f64,
+) -> Result<u64, String> {
+    let target_size_bytes = match *policy {
+        TargetPolicy::Ratio { percent } => {
+            if !(percent > 0.0 && percent < 100.0) {
+                return Err(format!(
+                    "Target ratio must be above 0% and below 100% (got {percent}%)."
+                ));
+            }
+            (input_size as f64 * percent / 100.0).floor() as u64
+        }
+        TargetPolicy::SizeMb { megabytes } => {
+            if !(megabytes > 0.0 && megabytes.is_finite()) {
+                return Err(format!(
+                    "Target size must be greater than 0 MB (got {megabytes} MB)."
+                ));
+            }
+            (megabytes * BYTES_PER_MEGABYTE).floor() as u64
+        }
+        TargetPolicy::MaxBitrate { kbps } => {
+            if kbps == 0 {
+                return Err("Maximum bitrate must be greater than 0 kbps.".to_string());
+            }
+            let cap_bytes = (kbps as f64 * 1000.0 * duration_sec / 8.0).floor() as u64;
+            return Ok(cap_bytes.min(input_size));
+        }
+    };
+
+    if target_size_bytes >= input_size {
+        return Err(format!(
+            "Target {policy} cannot be met: it allows {target_size_bytes} bytes, \
+             which is not smaller than the source ({input_size} bytes)."
+        ));
+    }
+
+    Ok(target_size_bytes)
+}
+
 fn compute_video_bitrate_kbps(
     target_size_bytes: u64,
     duration_sec: f64,
-    audio_bitrate_kbps: u32,
+    non_video_bitrate_kbps: u32,
 ) -> Result<u32, String> {
     let target_total_bitrate = ((target_size_bytes as f64 * 8.0) / duration_sec).floor();
     let target_total_kbps = (target_total_bitrate / 1000.0).floor() as i64;
-    let video_bitrate_kbps = target_total_kbps - audio_bitrate_kbps as i64;
+    let video_bitrate_kbps = target_total_kbps - non_video_bitrate_kbps as i64;
 
     if video_bitrate_kbps < MIN_VIDEO_BITRATE_KBPS {
         return Err(format!(
-            "Computed video bitrate too low ({video_bitrate_kbps} kbps). Use a larger source file."
+            "Computed video bitrate too low ({video_bitrate_kbps} kbps, minimum {MIN_VIDEO_BITRATE_KBPS} kbps)."
         ));
     }
 
     Ok(video_bitrate_kbps as u32)
 }
+
+#[cfg(test)]
+pub mod test_support {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::{ConversionPlan, RateControl};
+    use crate::convert::output_template::{
+        OutputNaming, OutputTemplate, TemplateValues, DEFAULT_OUTPUT_TEMPLATE,
+    };
+    use crate::convert::stream_selection::{OutputStreams, OutputTrack};
+    use crate::model::{CollisionPolicy, TargetPolicy};
+
+    pub fn sample_plan() -> ConversionPlan {
+        ConversionPlan {
+            ffmpeg_path: PathBuf::from("ffmpeg.exe"),
+            ffprobe_path: PathBuf::from("ffprobe.exe"),
+            output_path: PathBuf::from("clip.av1.mp4"),
+            temp_output_path: PathBuf::from("clip.av1.partial.mp4"),
+            duration_sec: 60.0,
+            rate_control: RateControl::TargetSize {
+                target_policy: TargetPolicy::default(),
+                target_size_bytes: 6_000_000,
+                video_bitrate_kbps: 672,
+            },
+            output_streams: OutputStreams {
+                video_index: 0,
+                video_packet_count: 1_800,
+                audio_tracks: vec![OutputTrack {
+                    input_index: 1,
+                    bitrate_kbps: 128,
+                    packet_count: 2_813,
+                }],
+                subtitle_tracks: Vec::new(),
+            },
+            encoder_candidates: vec!["libaom-av1".to_string()],
+            encoder_descriptions: HashMap::new(),
+            two_pass: true,
+            max_encode_attempts: 3,
+            vmaf_target: None,
+            preset_level: None,
+            job_id: None,
+            output_naming: OutputNaming {
+                dir: PathBuf::new(),
+                template: OutputTemplate::parse(DEFAULT_OUTPUT_TEMPLATE)
+                    .expect("default template should parse"),
+                values: TemplateValues {
+                    stem: "clip".to_string(),
+                    ext: "mkv".to_string(),
+                    height: None,
+                    date: "2026-10-18".to_string(),
+                    ratio_percent: Some(50.0),
+                },
+            },
+            collision_policy: CollisionPolicy::default(),
+            input_path: "clip.mkv".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    use super::test_support::sample_plan;
+    use super::{
+        build_encode_args, compute_video_bitrate_kbps, correct_video_bitrate_kbps,
+        resolve_preset_level, resolve_target_size_bytes, retain_constant_quality_encoders,
+        EncodePass, RateControl,
+    };
+    use crate::convert::output_template::OutputTemplate;
+    use crate::model::{CollisionPolicy, TargetPolicy};
+    use crate::test_fs::scratch_dir;
+
+    #[test]
+    fn resolve_target_size_bytes_applies_ratio() {
+        let target = resolve_target_size_bytes(&TargetPolicy::Ratio { percent: 30.0 }, 1_000, 10.0)
+            .expect("ratio target should resolve");
+        assert_eq!(target, 300);
+    }
+
+    #[test]
+    fn resolve_target_size_bytes_converts_megabytes() {
+        let target = resolve_target_size_bytes(
+            &TargetPolicy::SizeMb { megabytes: 2.0 },
+            10 * 1024 * 1024,
+            10.0,
+        )
+        .expect("size target should resolve");
+        assert_eq!(target, 2 * 1024 * 1024);
+    }
+
+    #[test]
+    fn resolve_target_size_bytes_converts_max_bitrate() {
+        let target =
+            resolve_target_size_bytes(&TargetPolicy::MaxBitrate { kbps: 800 }, 10_000_000, 60.0)
+                .expect("bitrate target should resolve");
+        assert_eq!(target, 6_000_000);
+    }
+
+    #[test]
+    fn resolve_target_size_bytes_rejects_out_of_range_ratio() {
+        let error = resolve_target_size_bytes(&TargetPolicy::Ratio { percent: 0.0 }, 1_000, 10.0)
+            .expect_err("zero ratio should fail");
+        assert!(error.contains("above 0% and below 100%"));
+        let error = resolve_target_size_bytes(&TargetPolicy::Ratio { percent: 100.0 }, 1_000, 10.0)
+            .expect_err("a ratio of 100% should fail");
+        assert!(error.contains("above 0% and below 100%"));
+    }
+
+    #[test]
+    fn resolve_target_size_bytes_rejects_target_not_below_source() {
+        let error =
+            resolve_target_size_bytes(&TargetPolicy::SizeMb { megabytes: 2.0 }, 1_000_000, 60.0)
+                .expect_err("target above source size should fail");
+        assert!(error.contains("not smaller than the source"));
+    }
+
+    #[test]
+    fn resolve_target_size_bytes_clamps_max_bitrate_to_source() {
+        let target =
+            resolve_target_size_bytes(&TargetPolicy::MaxBitrate { kbps: 10_000 }, 1_000_000, 60.0)
+                .expect("a cap above the source bitrate should clamp");
+        assert_eq!(target, 1_000_000);
+    }
+
+    #[test]
+    fn compute_video_bitrate_kbps_rejects_bitrate_below_minimum() {
+        let error =
+            compute_video_bitrate_kbps(100_000, 60.0, 128).expect_err("tiny target should fail");
+        assert!(error.contains("too low"));
+    }
+
+    #[test]
+    fn build_encode_args_analysis_pass_discards_output() {
+        let plan = sample_plan();
+        let log_prefix = Path::new("passlog");
+        let args = build_encode_args(&plan, "libaom-av1", EncodePass::Analysis { log_prefix });
+
+        assert!(args.windows(2).any(|pair| pair == ["-pass", "1"]));
+        assert!(args
+            .windows(2)
+            .any(|pair| pair == ["-passlogfile", "passlog"]));
+        assert!(!args.contains(&"-minrate".to_string()));
+        assert_eq!(&args[args.len() - 3..], ["-f", "null", "-"]);
+    }
+
+    #[test]
+    fn build_encode_args_final_pass_writes_output() {
+        let plan = sample_plan();
+        let log_prefix = Path::new("passlog");
+        let args = build_encode_args(&plan, "libaom-av1", EncodePass::Final { log_prefix });
+
+        assert!(args.windows(2).any(|pair| pair == ["-pass", "2"]));
+        assert!(args.windows(2).any(|pair| pair == ["-b:a:0", "128k"]));
+        assert!(!args.contains(&"-maxrate".to_string()));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("clip.av1.partial.mp4")
+        );
+    }
+
+    #[test]
+    fn correct_video_bitrate_kbps_scales_down_on_overshoot() {
+        let corrected = correct_video_bitrate_kbps(1_000, 1_000_000, 1_100_000, 0)
+            .expect("correction should succeed");
+        assert!(corrected < 1_000 * 1_000_000 / 1_100_000);
+        assert!(corrected > 850);
+    }
+
+    #[test]
+    fn correct_video_bitrate_kbps_rescales_only_the_video_share() {
+        let corrected = correct_video_bitrate_kbps(1_000, 1_000_000, 1_100_000, 500_000)
+            .expect("correction should succeed");
+        assert_eq!(
+            corrected,
+            (1_000.0_f64 * 500_000.0 / 600_000.0 * 0.98).floor() as u32
+        );
+
+        let error = correct_video_bitrate_kbps(1_000, 1_000_000, 1_100_000, 1_000_000)
+            .expect_err("no room for video should fail");
+        assert!(error.contains("no room for video"));
+    }
+
+    #[test]
+    fn correct_video_bitrate_kbps_rejects_bitrate_below_minimum() {
+        let error = correct_video_bitrate_kbps(120, 1_000_000, 2_000_000, 0)
+            .expect_err("correction below minimum should fail");
+        assert!(error.contains("too low"));
+    }
+
+    #[test]
+    fn two_pass_plans_run_svt_av1_in_a_single_pass() {
+        let mut plan = sample_plan();
+        plan.encoder_candidates = vec!["libsvtav1".to_string(), "libaom-av1".to_string()];
+
+        assert!(!plan.uses_two_pass("libsvtav1"));
+        assert!(plan.uses_two_pass("libaom-av1"));
+        assert!(plan.uses_two_pass("librav1e"));
+    }
+
+    #[test]
+    fn build_encode_args_adds_preset_after_encoder() {
+        let mut plan = sample_plan();
+        plan.preset_level = Some(2);
+        let args = build_encode_args(&plan, "libaom-av1", EncodePass::Single);
+
+        assert!(args
+            .windows(4)
+            .any(|window| window == ["-c:v", "libaom-av1", "-cpu-used", "6"]));
+        assert_eq!(resolve_preset_level(None), Ok(None));
+        assert!(resolve_preset_level(Some(0)).is_err());
+        assert!(resolve_preset_level(Some(6)).is_err());
+    }
+
+    #[test]
+    fn build_encode_args_uses_quality_knob_in_constant_quality_mode() {
+        let mut plan = sample_plan();
+        plan.rate_control = RateControl::ConstantQuality { quality_level: 30 };
+        let args = build_encode_args(&plan, "libsvtav1", EncodePass::Single);
+
+        assert!(args.windows(2).any(|pair| pair == ["-crf", "30"]));
+        assert!(!args.contains(&"-minrate".to_string()));
+    }
+
+    #[test]
+    fn retain_constant_quality_encoders_drops_unsupported_fallbacks() {
+        let candidates = vec!["av1_nvenc".to_string(), "my_custom_av1".to_string()];
+        let retained = retain_constant_quality_encoders(candidates, None, &HashMap::new())
+            .expect("nvenc should be retained");
+        assert_eq!(retained, vec!["av1_nvenc".to_string()]);
+    }
+
+    #[test]
+    fn retain_constant_quality_encoders_rejects_unsupported_requested_encoder() {
+        let candidates = vec!["my_custom_av1".to_string(), "libsvtav1".to_string()];
+        let error =
+            retain_constant_quality_encoders(candidates, Some("my_custom_av1"), &HashMap::new())
+                .expect_err("unsupported requested encoder should fail");
+        assert!(error.contains("does not support constant-quality mode"));
+    }
+
+    #[test]
+    fn retarget_keeps_the_reserved_name_when_the_fallback_name_is_taken() {
+        let dir = scratch_dir("planning-retarget");
+        fs::write(dir.join("clip-av1_qsv.mp4"), b"").expect("file should be written");
+        let mut plan = sample_plan();
+        plan.encoder_candidates = vec!["av1_nvenc".to_string(), "av1_qsv".to_string()];
+        plan.collision_policy = CollisionPolicy::Skip;
+        plan.output_naming.dir = dir.clone();
+        plan.output_naming.template =
+            OutputTemplate::parse("{stem}-{encoder}").expect("template should parse");
+
+        let reserved = plan
+            .reserve_output(&|_| true)
+            .map(|_| plan.output_path.clone());
+        let retargeted = plan.retarget_output("av1_qsv", &|_| true);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            reserved.expect("primary name should be free"),
+            dir.join("clip-av1_nvenc.mp4")
+        );
+        assert_eq!(retargeted, Ok(None));
+        assert_eq!(plan.output_path, dir.join("clip-av1_nvenc.mp4"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use crate::model::{ConversionPreview, EncoderPreview, FfmpegInvocation};
+
+use super::ffmpeg_runner::with_progress_args;
+use super::planning::{single_pass_step, ConversionPlan, EncodeStep};
+use super::two_pass::{two_pass_steps, PASS_LOG_FILE_PREFIX};
+use super::vmaf_search::{first_search_steps, MAX_SEARCH_STEPS};
+
+const PASS_LOG_DIR_PLACEHOLDER: &str = "<pass-log temp folder>";
+const VMAF_DIR_PLACEHOLDER: &str = "<vmaf temp folder>";
+
+pub fn preview_plan(plan: &ConversionPlan) -> ConversionPreview {
+    let encoders = plan
+        .encoder_candidates
+        .iter()
+        .map(|encoder| preview_encoder(plan, encoder))
+        .collect();
+
+    ConversionPreview {
+        input_path: plan.input_path().to_string(),
+        output_path: plan.output_path.to_string_lossy().to_string(),
+        ffmpeg_path: plan.ffmpeg_path.to_string_lossy().to_string(),
+        ffprobe_path: plan.ffprobe_path.to_string_lossy().to_string(),
+        encoder_candidates: plan.encoder_candidates.clone(),
+        encoders,
+    }
+}
+
+fn preview_encoder(plan: &ConversionPlan, encoder: &str) -> EncoderPreview {
+    let mut invocations = Vec::new();
+    let mut encode_notes = Vec::new();
+
+    if plan.vmaf_target.is_some() {
+        let (level, steps) = first_search_steps(plan, encoder, Path::new(VMAF_DIR_PLACEHOLDER));
+        let search_notes = vec![
+            format!("{VMAF_DIR_PLACEHOLDER} is a temp folder created for each run."),
+            format!(
+                "Only the first search step (level {level}) is shown; up to {MAX_SEARCH_STEPS} steps run, each at a level picked from the previous scores."
+            ),
+        ];
+        invocations.extend(
+            steps
+                .into_iter()
+                .map(|step| to_invocation(step, search_notes.clone())),
+        );
+        if let Some(quality_level) = plan.quality_level() {
+            encode_notes.push(format!(
+                "The quality level is chosen by the VMAF search; level {quality_level} is shown in its place."
+            ));
+        }
+    }
+
+    let two_pass = plan.uses_two_pass(encoder);
+    if two_pass {
+        let log_prefix = Path::new(PASS_LOG_DIR_PLACEHOLDER).join(PASS_LOG_FILE_PREFIX);
+        encode_notes.push(format!(
+            "{PASS_LOG_DIR_PLACEHOLDER} is a temp folder created for each run."
+        ));
+        invocations.extend(
+            two_pass_steps(plan, encoder, &log_prefix, "")
+                .into_iter()
+                .map(|step| to_invocation(step, encode_notes.clone())),
+        );
+    } else {
+        invocations.push(to_invocation(
+            single_pass_step(plan, encoder, ""),
+            encode_notes,
+        ));
+    }
+
+    EncoderPreview {
+        encoder: encoder.to_string(),
+        two_pass,
+        invocations,
+    }
+}
+
+fn to_invocation(step: EncodeStep, runtime_values: Vec<String>) -> FfmpegInvocation {
+    FfmpegInvocation {
+        label: step.label,
+        args: with_progress_args(&step.args),
+        runtime_values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::preview_plan;
+    use crate::convert::planning::test_support::sample_plan;
+    use crate::convert::planning::RateControl;
+
+    #[test]
+    fn vmaf_preview_lists_search_steps_and_marks_run_time_values() {
+        let mut plan = sample_plan();
+        plan.two_pass = false;
+        plan.vmaf_target = Some(95.0);
+        plan.rate_control = RateControl::ConstantQuality { quality_level: 32 };
+        let preview = preview_plan(&plan);
+        let invocations = &preview.encoders[0].invocations;
+
+        assert_eq!(
+            invocations
+                .iter()
+                .map(|invocation| invocation.label.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "VMAF search 1/6 (level 36, sample 1/3): encode",
+                "VMAF search 1/6 (level 36, sample 1/3): score",
+                "VMAF search 1/6 (level 36, sample 2/3): encode",
+                "VMAF search 1/6 (level 36, sample 2/3): score",
+                "VMAF search 1/6 (level 36, sample 3/3): encode",
+                "VMAF search 1/6 (level 36, sample 3/3): score",
+                "Encode",
+            ]
+        );
+        assert!(!preview.encoders[0].two_pass);
+        assert!(invocations[0]
+            .args
+            .iter()
+            .any(|arg| arg.starts_with("<vmaf temp folder>")));
+        assert!(!invocations[6]
+            .args
+            .iter()
+            .any(|arg| arg.starts_with("<pass-log temp folder>")));
+        assert!(invocations[6]
+            .runtime_values
+            .iter()
+            .any(|note| note.contains("chosen by the VMAF search")));
+    }
+}
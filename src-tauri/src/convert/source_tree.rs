@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn mirrored_output_dir(
+    source_root: &Path,
+    input_path: &Path,
+    output_dir: &Path,
+) -> Result<PathBuf, String> {
+    let input_dir = input_path
+        .parent()
+        .ok_or_else(|| "Input path has no parent folder.".to_string())?;
+    let relative_dir = match input_dir.strip_prefix(source_root) {
+        Ok(relative_dir) => relative_dir.to_path_buf(),
+        Err(_) => canonical_relative_dir(source_root, input_dir).ok_or_else(|| {
+            format!(
+                "Input {} is not inside the source folder {}.",
+                input_path.to_string_lossy(),
+                source_root.to_string_lossy()
+            )
+        })?,
+    };
+    Ok(output_dir.join(relative_dir))
+}
+
+fn canonical_relative_dir(source_root: &Path, input_dir: &Path) -> Option<PathBuf> {
+    let source_root = fs::canonicalize(source_root).ok()?;
+    let input_dir = fs::canonicalize(input_dir).ok()?;
+    input_dir
+        .strip_prefix(source_root)
+        .ok()
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::mirrored_output_dir;
+
+    #[test]
+    fn mirrored_output_dir_keeps_relative_layout() {
+        let root = Path::new("footage");
+        let archive = Path::new("archive").join("av1");
+
+        let nested = mirrored_output_dir(
+            root,
+            &root.join("2024").join("trip").join("clip.mkv"),
+            &archive,
+        )
+        .expect("nested input should map");
+        let top_level = mirrored_output_dir(root, &root.join("clip.mkv"), &archive)
+            .expect("top-level input should map");
+
+        assert_eq!(nested, archive.join("2024").join("trip"));
+        assert_eq!(top_level, archive);
+    }
+
+    #[test]
+    fn mirrored_output_dir_rejects_input_outside_root() {
+        let result = mirrored_output_dir(
+            Path::new("footage"),
+            Path::new("elsewhere/clip.mkv"),
+            Path::new("archive"),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::ffmpeg::{MediaInfo, StreamKind};
+
+use super::container_overhead::{
+    estimate_aac_packets, estimate_subtitle_packets, estimate_video_packets,
+};
+
+const AUDIO_BITRATE_FALLBACK_KBPS: u32 = 128;
+const SUBTITLE_BITRATE_FALLBACK_KBPS: u32 = 2;
+const MP4_TEXT_SUBTITLE_CODECS: &[&str] = &["subrip", "ass", "ssa", "mov_text", "webvtt", "text"];
+
+pub struct OutputStreams {
+    pub video_index: u32,
+    pub video_packet_count: u64,
+    pub audio_tracks: Vec<OutputTrack>,
+    pub subtitle_tracks: Vec<OutputTrack>,
+}
+
+pub struct OutputTrack {
+    pub input_index: u32,
+    pub bitrate_kbps: u32,
+    pub packet_count: u64,
+}
+
+impl OutputStreams {
+    pub fn audio_bitrate_kbps(&self) -> u32 {
+        self.audio_tracks
+            .iter()
+            .map(|track| track.bitrate_kbps)
+            .sum()
+    }
+
+    pub fn non_video_bitrate_kbps(&self) -> u32 {
+        self.audio_bitrate_kbps()
+            + self
+                .subtitle_tracks
+                .iter()
+                .map(|track| track.bitrate_kbps)
+                .sum::<u32>()
+    }
+
+    pub fn track_count(&self) -> usize {
+        1 + self.audio_tracks.len() + self.subtitle_tracks.len()
+    }
+
+    pub fn packet_count(&self) -> u64 {
+        self.video_packet_count
+            + self
+                .audio_tracks
+                .iter()
+                .chain(&self.subtitle_tracks)
+                .map(|track| track.packet_count)
+                .sum::<u64>()
+    }
+
+    pub fn video_map_args(&self) -> Vec<String> {
+        vec!["-map".to_string(), format!("0:{}", self.video_index)]
+    }
+
+    pub fn map_args(&self) -> Vec<String> {
+        let mut args = self.video_map_args();
+        for track in self.audio_tracks.iter().chain(&self.subtitle_tracks) {
+            args.push("-map".to_string());
+            args.push(format!("0:{}", track.input_index));
+        }
+        args
+    }
+
+    pub fn codec_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.audio_tracks.is_empty() {
+            args.extend(["-c:a".to_string(), "aac".to_string()]);
+            for (output_index, track) in self.audio_tracks.iter().enumerate() {
+                args.push(format!("-b:a:{output_index}"));
+                args.push(format!("{}k", track.bitrate_kbps));
+            }
+        }
+        if !self.subtitle_tracks.is_empty() {
+            args.extend(["-c:s".to_string(), "mov_text".to_string()]);
+        }
+        args
+    }
+}
+
+pub fn select_output_streams(media: &MediaInfo) -> Result<OutputStreams, String> {
+    let duration_sec = media.duration_sec;
+    let video = media
+        .primary_video()
+        .ok_or_else(|| "Input has no video stream.".to_string())?;
+
+    let audio_tracks = media
+        .streams_of(StreamKind::Audio)
+        .map(|stream| OutputTrack {
+            input_index: stream.index,
+            bitrate_kbps: stream.bit_rate_kbps.unwrap_or(AUDIO_BITRATE_FALLBACK_KBPS),
+            packet_count: estimate_aac_packets(
+                stream.audio.as_ref().and_then(|audio| audio.sample_rate),
+                duration_sec,
+            ),
+        })
+        .collect();
+
+    let subtitle_tracks = media
+        .streams_of(StreamKind::Subtitle)
+        .filter(|stream| MP4_TEXT_SUBTITLE_CODECS.contains(&stream.codec_name.as_str()))
+        .map(|stream| OutputTrack {
+            input_index: stream.index,
+            bitrate_kbps: stream
+                .bit_rate_kbps
+                .unwrap_or(SUBTITLE_BITRATE_FALLBACK_KBPS),
+            packet_count: estimate_subtitle_packets(stream.frame_count, duration_sec),
+        })
+        .collect();
+
+    Ok(OutputStreams {
+        video_index: video.index,
+        video_packet_count: estimate_video_packets(
+            video.frame_count,
+            video.video.as_ref().and_then(|details| details.frame_rate),
+            duration_sec,
+        ),
+        audio_tracks,
+        subtitle_tracks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_output_streams;
+    use crate::ffmpeg::{MediaInfo, StreamInfo, StreamKind};
+
+    fn stream(index: u32, kind: StreamKind, codec_name: &str, kbps: Option<u32>) -> StreamInfo {
+        StreamInfo {
+            index,
+            kind,
+            codec_name: codec_name.to_string(),
+            profile: None,
+            bit_rate_kbps: kbps,
+            frame_count: None,
+            language: None,
+            title: None,
+            disposition: Default::default(),
+            video: None,
+            audio: None,
+        }
+    }
+
+    fn media(streams: Vec<StreamInfo>) -> MediaInfo {
+        MediaInfo {
+            format_name: "matroska,webm".to_string(),
+            duration_sec: 10.0,
+            size_bytes: None,
+            bit_rate_kbps: None,
+            streams,
+            chapters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn select_output_streams_budgets_nothing_for_silent_input() {
+        let input = media(vec![stream(0, StreamKind::Video, "h264", Some(5_000))]);
+        let selected = select_output_streams(&input).expect("video stream should be selected");
+
+        assert_eq!(selected.non_video_bitrate_kbps(), 0);
+        assert_eq!(selected.map_args(), ["-map", "0:0"]);
+        assert!(selected.codec_args().is_empty());
+    }
+
+    #[test]
+    fn select_output_streams_sums_every_audio_track() {
+        let input = media(vec![
+            stream(0, StreamKind::Video, "h264", None),
+            stream(1, StreamKind::Audio, "ac3", Some(384)),
+            stream(2, StreamKind::Audio, "aac", None),
+            stream(3, StreamKind::Subtitle, "subrip", None),
+            stream(4, StreamKind::Subtitle, "hdmv_pgs_subtitle", Some(40)),
+            stream(5, StreamKind::Data, "bin_data", Some(1)),
+        ]);
+        let selected = select_output_streams(&input).expect("streams should be selected");
+
+        assert_eq!(selected.audio_bitrate_kbps(), 384 + 128);
+        assert_eq!(selected.non_video_bitrate_kbps(), 384 + 128 + 2);
+        assert_eq!(selected.track_count(), 4);
+        assert_eq!(selected.packet_count(), 300 + 469 + 469 + 5);
+        assert_eq!(
+            selected.map_args(),
+            ["-map", "0:0", "-map", "0:1", "-map", "0:2", "-map", "0:3"]
+        );
+        assert_eq!(
+            selected.codec_args(),
+            ["-c:a", "aac", "-b:a:0", "384k", "-b:a:1", "128k", "-c:s", "mov_text"]
+        );
+    }
+
+    #[test]
+    fn select_output_streams_drops_data_streams_on_purpose() {
+        let input = media(vec![
+            stream(0, StreamKind::Video, "prores", Some(50_000)),
+            stream(1, StreamKind::Data, "tmcd", Some(1)),
+            stream(2, StreamKind::Data, "bin_data", Some(64)),
+        ]);
+        let selected = select_output_streams(&input).expect("video stream should be selected");
+
+        assert_eq!(selected.map_args(), ["-map", "0:0"]);
+        assert_eq!(selected.track_count(), 1);
+        assert_eq!(selected.non_video_bitrate_kbps(), 0);
+    }
+
+    #[test]
+    fn select_output_streams_skips_cover_art() {
+        let mut cover = stream(0, StreamKind::Video, "mjpeg", None);
+        cover.disposition.attached_pic = true;
+        let input = media(vec![cover, stream(1, StreamKind::Video, "hevc", None)]);
+        let selected = select_output_streams(&input).expect("video stream should be selected");
+
+        assert_eq!(selected.video_index, 1);
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct TempOutput {
+    path: PathBuf,
+    published: bool,
+}
+
+impl TempOutput {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            published: false,
+        }
+    }
+
+    fn discard(&self) {
+        if !self.path.exists() {
+            return;
+        }
+        let _ = fs::remove_file(&self.path);
+    }
+
+    pub fn truncate(&self) {
+        let _ = fs::File::create(&self.path);
+    }
+
+    pub fn publish(mut self, output_path: &Path) -> Result<(), String> {
+        fs::rename(&self.path, output_path).map_err(|e| {
+            format!(
+                "Failed to move encoded output into place at {}: {e}",
+                output_path.to_string_lossy()
+            )
+        })?;
+        self.published = true;
+        Ok(())
+    }
+}
+
+impl Drop for TempOutput {
+    fn drop(&mut self) {
+        if !self.published {
+            self.discard();
+        }
+    }
+}
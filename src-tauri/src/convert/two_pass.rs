@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+
+use super::cancellation::abort_if_stop_requested;
+use super::ffmpeg_runner::run_ffmpeg_with_progress;
+use super::planning::{build_encode_args, ConversionPlan, EncodePass, EncodeStep};
+use super::scratch_dir::ScratchDir;
+
+pub const PASS_LOG_FILE_PREFIX: &str = "ffmpeg2pass";
+
+pub fn run_two_pass_encode(
+    app: &AppHandle,
+    plan: &ConversionPlan,
+    encoder: &str,
+    label_suffix: &str,
+) -> Result<(), String> {
+    let log_dir = ScratchDir::create("passlog")?;
+    let log_prefix = log_dir.path().join(PASS_LOG_FILE_PREFIX);
+
+    for (index, step) in two_pass_steps(plan, encoder, &log_prefix, label_suffix)
+        .iter()
+        .enumerate()
+    {
+        if index > 0 {
+            abort_if_stop_requested(app, plan.job_id)?;
+        }
+        run_ffmpeg_with_progress(
+            app,
+            plan.job_id,
+            &plan.ffmpeg_path,
+            &step.args,
+            plan.duration_sec,
+            &step.label,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn two_pass_steps(
+    plan: &ConversionPlan,
+    encoder: &str,
+    log_prefix: &Path,
+    label_suffix: &str,
+) -> [EncodeStep; 2] {
+    [
+        EncodeStep {
+            label: format!("Pass 1/2 (analysis){label_suffix}"),
+            args: build_encode_args(plan, encoder, EncodePass::Analysis { log_prefix }),
+        },
+        EncodeStep {
+            label: format!("Pass 2/2 (encode){label_suffix}"),
+            args: build_encode_args(plan, encoder, EncodePass::Final { log_prefix }),
+        },
+    ]
+}
@@ -0,0 +1,26 @@
+use std::fs;
+use std::path::Path;
+
+const SIZE_TOLERANCE_PERCENT: f64 = 2.0;
+
+pub fn measure_output_size(output_path: &Path) -> Result<u64, String> {
+    fs::metadata(output_path)
+        .map(|metadata| metadata.len())
+        .map_err(|e| format!("Could not read encoded output metadata: {e}"))
+}
+
+pub fn is_within_target(output_size_bytes: u64, target_size_bytes: u64) -> bool {
+    let limit = target_size_bytes as f64 * (1.0 + SIZE_TOLERANCE_PERCENT / 100.0);
+    output_size_bytes as f64 <= limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_within_target;
+
+    #[test]
+    fn is_within_target_allows_small_overshoot() {
+        assert!(is_within_target(1_015_000, 1_000_000));
+        assert!(!is_within_target(1_050_000, 1_000_000));
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+
+use crate::ffmpeg::{parse_vmaf_log, MAX_QUALITY_LEVEL};
+use crate::model::{VmafSearchResult, VmafSearchStep};
+
+use super::cancellation::abort_if_stop_requested;
+use super::ffmpeg_runner::run_ffmpeg_with_progress;
+use super::planning::{
+    build_sample_encode_args, build_vmaf_score_args, ConversionPlan, EncodeStep,
+};
+use super::scratch_dir::ScratchDir;
+
+const SAMPLE_COUNT: usize = 3;
+const SAMPLE_DURATION_SEC: f64 = 4.0;
+const MIN_SEARCH_LEVEL: u32 = 10;
+pub const MAX_SEARCH_STEPS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SampleWindow {
+    start_sec: f64,
+    duration_sec: f64,
+}
+
+struct SampleRun {
+    encode: EncodeStep,
+    score: EncodeStep,
+    sample_path: PathBuf,
+    log_path: PathBuf,
+    duration_sec: f64,
+}
+
+pub fn first_search_steps(
+    plan: &ConversionPlan,
+    encoder: &str,
+    scratch_dir: &Path,
+) -> (u32, Vec<EncodeStep>) {
+    let level = search_level(MIN_SEARCH_LEVEL, MAX_QUALITY_LEVEL);
+    let steps = sample_runs(
+        plan,
+        encoder,
+        level,
+        &sample_windows(plan.duration_sec),
+        scratch_dir,
+        1,
+    )
+    .into_iter()
+    .flat_map(|run| [run.encode, run.score])
+    .collect();
+    (level, steps)
+}
+
+pub fn search_vmaf_quality_level(
+    app: &AppHandle,
+    plan: &ConversionPlan,
+    encoder: &str,
+    target_score: f64,
+) -> Result<VmafSearchResult, String> {
+    let scratch_dir = ScratchDir::create("vmaf")?;
+    let windows = sample_windows(plan.duration_sec);
+
+    let mut steps: Vec<VmafSearchStep> = Vec::new();
+    let mut low = MIN_SEARCH_LEVEL;
+    let mut high = MAX_QUALITY_LEVEL;
+    let mut best_level: Option<u32> = None;
+
+    while low <= high && steps.len() < MAX_SEARCH_STEPS {
+        abort_if_stop_requested(app, plan.job_id)?;
+
+        let level = search_level(low, high);
+        let step = score_quality_level(
+            app,
+            plan,
+            encoder,
+            level,
+            &windows,
+            scratch_dir.path(),
+            steps.len() + 1,
+        )?;
+        let meets_target = step.mean_score >= target_score;
+        steps.push(step);
+
+        if meets_target {
+            best_level = Some(level);
+            low = level + 1;
+        } else if level == 0 {
+            break;
+        } else {
+            high = level - 1;
+        }
+    }
+
+    let quality_level = best_level
+        .or_else(|| steps.iter().map(|step| step.quality_level).min())
+        .ok_or_else(|| "VMAF search did not score any sample.".to_string())?;
+
+    Ok(VmafSearchResult {
+        target_score,
+        quality_level,
+        target_met: best_level.is_some(),
+        sample_starts_sec: windows.iter().map(|window| window.start_sec).collect(),
+        steps,
+    })
+}
+
+fn score_quality_level(
+    app: &AppHandle,
+    plan: &ConversionPlan,
+    encoder: &str,
+    quality_level: u32,
+    windows: &[SampleWindow],
+    scratch_dir: &Path,
+    step_number: usize,
+) -> Result<VmafSearchStep, String> {
+    let mut sample_scores = Vec::with_capacity(windows.len());
+
+    for run in sample_runs(
+        plan,
+        encoder,
+        quality_level,
+        windows,
+        scratch_dir,
+        step_number,
+    ) {
+        abort_if_stop_requested(app, plan.job_id)?;
+
+        for step in [&run.encode, &run.score] {
+            run_ffmpeg_with_progress(
+                app,
+                plan.job_id,
+                &plan.ffmpeg_path,
+                &step.args,
+                run.duration_sec,
+                &step.label,
+            )?;
+        }
+
+        let log_text = fs::read_to_string(&run.log_path)
+            .map_err(|e| format!("Failed to read VMAF log: {e}"))?;
+        sample_scores.push(parse_vmaf_log(&log_text)?);
+        let _ = fs::remove_file(&run.sample_path);
+    }
+
+    let mean_score = sample_scores.iter().sum::<f64>() / sample_scores.len().max(1) as f64;
+    Ok(VmafSearchStep {
+        quality_level,
+        sample_scores,
+        mean_score,
+    })
+}
+
+fn sample_runs(
+    plan: &ConversionPlan,
+    encoder: &str,
+    quality_level: u32,
+    windows: &[SampleWindow],
+    scratch_dir: &Path,
+    step_number: usize,
+) -> Vec<SampleRun> {
+    windows
+        .iter()
+        .enumerate()
+        .map(|(index, window)| {
+            let sample_label = format!(
+                "VMAF search {step_number}/{MAX_SEARCH_STEPS} (level {quality_level}, sample {}/{})",
+                index + 1,
+                windows.len()
+            );
+            let sample_path =
+                scratch_dir.join(format!("level-{quality_level}-sample-{index}.mp4"));
+            let log_path = scratch_dir.join(format!("level-{quality_level}-sample-{index}.json"));
+            SampleRun {
+                encode: EncodeStep {
+                    label: format!("{sample_label}: encode"),
+                    args: build_sample_encode_args(
+                        plan,
+                        encoder,
+                        quality_level,
+                        window.start_sec,
+                        window.duration_sec,
+                        &sample_path,
+                    ),
+                },
+                score: EncodeStep {
+                    label: format!("{sample_label}: score"),
+                    args: build_vmaf_score_args(
+                        plan,
+                        window.start_sec,
+                        window.duration_sec,
+                        &sample_path,
+                        &log_path,
+                    ),
+                },
+                sample_path,
+                log_path,
+                duration_sec: window.duration_sec,
+            }
+        })
+        .collect()
+}
+
+fn search_level(low: u32, high: u32) -> u32 {
+    low + (high - low) / 2
+}
+
+fn sample_windows(duration_sec: f64) -> Vec<SampleWindow> {
+    if duration_sec <= SAMPLE_DURATION_SEC * SAMPLE_COUNT as f64 {
+        return vec![SampleWindow {
+            start_sec: 0.0,
+            duration_sec,
+        }];
+    }
+
+    (0..SAMPLE_COUNT)
+        .map(|index| {
+            let center = duration_sec * (index + 1) as f64 / (SAMPLE_COUNT + 1) as f64;
+            SampleWindow {
+                start_sec: (center - SAMPLE_DURATION_SEC / 2.0).max(0.0),
+                duration_sec: SAMPLE_DURATION_SEC,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sample_windows, SampleWindow, SAMPLE_COUNT, SAMPLE_DURATION_SEC};
+
+    #[test]
+    fn sample_windows_spreads_samples_across_the_file() {
+        let windows = sample_windows(400.0);
+
+        assert_eq!(windows.len(), SAMPLE_COUNT);
+        assert_eq!(windows[0].start_sec, 98.0);
+        assert_eq!(windows[1].start_sec, 198.0);
+        assert_eq!(windows[2].start_sec, 298.0);
+        assert!(windows
+            .iter()
+            .all(|window| window.duration_sec == SAMPLE_DURATION_SEC));
+    }
+
+    #[test]
+    fn sample_windows_uses_whole_file_for_short_clips() {
+        assert_eq!(
+            sample_windows(6.5),
+            vec![SampleWindow {
+                start_sec: 0.0,
+                duration_sec: 6.5,
+            }]
+        );
+    }
+}
@@ -1,24 +1,205 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::UNIX_EPOCH;
+
 use tauri::AppHandle;
 
-use crate::ffmpeg::{list_encoders, resolve_encoder_candidates, resolve_tool_path};
-use crate::state::{get_cached_av1_encoders, set_cached_av1_encoders};
+use crate::ffmpeg::{
+    describe_encoder, list_encoders, resolve_encoder_candidates, resolve_tool_path, test_encoder,
+    EncoderDescription,
+};
+use crate::model::EncoderCheck;
+use crate::state::{
+    clear_cached_av1_encoders, encoder_cache_file_path, get_cached_av1_encoders,
+    load_encoder_checks, remove_encoder_checks, save_encoder_checks, set_cached_av1_encoders,
+    EncoderCacheKey,
+};
 
-pub fn get_available_av1_encoders(app: &AppHandle) -> Result<Vec<String>, String> {
+pub fn get_av1_encoder_checks(app: &AppHandle) -> Result<Vec<EncoderCheck>, String> {
     if let Some(cached) = get_cached_av1_encoders(app)? {
         return Ok(cached);
     }
 
     let ffmpeg_path = resolve_tool_path(app, "ffmpeg.exe")?;
-    let discovered = list_encoders(&ffmpeg_path)?;
-    set_cached_av1_encoders(app, discovered.clone())?;
-    Ok(discovered)
+    let cache_key = encoder_cache_key(&ffmpeg_path)?;
+    let cache_path = encoder_cache_file_path(app)?;
+    if let Ok(Some(checks)) = load_encoder_checks(&cache_path, &cache_key) {
+        set_cached_av1_encoders(app, checks.clone())?;
+        return Ok(checks);
+    }
+
+    let checks = discover_av1_encoders(&ffmpeg_path)?;
+    let _ = save_encoder_checks(&cache_path, &cache_key, &checks);
+    set_cached_av1_encoders(app, checks.clone())?;
+    Ok(checks)
+}
+
+pub fn refresh_av1_encoders(app: &AppHandle) -> Result<Vec<EncoderCheck>, String> {
+    clear_cached_av1_encoders(app)?;
+    remove_encoder_checks(&encoder_cache_file_path(app)?)?;
+    get_av1_encoder_checks(app)
+}
+
+fn encoder_cache_key(ffmpeg_path: &Path) -> Result<EncoderCacheKey, String> {
+    let metadata = fs::metadata(ffmpeg_path)
+        .map_err(|e| format!("Could not read {}: {e}", ffmpeg_path.to_string_lossy()))?;
+    let modified_unix_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+    Ok(EncoderCacheKey {
+        ffmpeg_path: ffmpeg_path.to_string_lossy().to_string(),
+        size_bytes: metadata.len(),
+        modified_unix_ms,
+    })
+}
+
+fn discover_av1_encoders(ffmpeg_path: &Path) -> Result<Vec<EncoderCheck>, String> {
+    let discovered = list_encoders(ffmpeg_path)?;
+    Ok(thread::scope(|scope| {
+        let handles: Vec<_> = discovered
+            .iter()
+            .map(|encoder| scope.spawn(|| check_encoder(ffmpeg_path, encoder)))
+            .collect();
+        discovered
+            .iter()
+            .zip(handles)
+            .map(|(encoder, handle)| {
+                handle.join().unwrap_or_else(|_| EncoderCheck {
+                    encoder: encoder.clone(),
+                    usable: false,
+                    error: Some("Test encode thread panicked.".to_string()),
+                    description: None,
+                })
+            })
+            .collect()
+    }))
+}
+
+fn check_encoder(ffmpeg_path: &Path, encoder: &str) -> EncoderCheck {
+    let (usable, error, description) = match test_encoder(ffmpeg_path, encoder) {
+        Ok(()) => match describe_encoder(ffmpeg_path, encoder) {
+            Ok(description) => (true, None, Some(description)),
+            Err(error) => (true, Some(error), None),
+        },
+        Err(error) => (false, Some(error), None),
+    };
+    EncoderCheck {
+        encoder: encoder.to_string(),
+        usable,
+        error,
+        description,
+    }
+}
+
+pub fn get_available_av1_encoders(
+    app: &AppHandle,
+    requested: Option<&str>,
+) -> Result<Vec<String>, String> {
+    usable_encoders(&get_av1_encoder_checks(app)?, requested)
+}
+
+pub fn get_av1_encoder_descriptions(
+    app: &AppHandle,
+) -> Result<HashMap<String, EncoderDescription>, String> {
+    Ok(get_av1_encoder_checks(app)?
+        .into_iter()
+        .filter_map(|check| Some((check.encoder, check.description?)))
+        .collect())
 }
 
 pub fn pick_auto_av1_encoder(app: &AppHandle) -> Result<String, String> {
-    let available = get_available_av1_encoders(app)?;
+    let available = get_available_av1_encoders(app, None)?;
     let candidates = resolve_encoder_candidates(None, &available)?;
     candidates
         .into_iter()
         .next()
         .ok_or_else(|| "No AV1 encoder selected.".to_string())
 }
+
+fn usable_encoders(
+    checks: &[EncoderCheck],
+    requested: Option<&str>,
+) -> Result<Vec<String>, String> {
+    if let Some(failed) = checks
+        .iter()
+        .find(|check| Some(check.encoder.as_str()) == requested && !check.usable)
+    {
+        return Err(format!(
+            "Requested AV1 encoder {} failed its test encode: {}",
+            failed.encoder,
+            failed.error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+
+    let usable: Vec<String> = checks
+        .iter()
+        .filter(|check| check.usable)
+        .map(|check| check.encoder.clone())
+        .collect();
+    if usable.is_empty() && !checks.is_empty() {
+        let reasons: Vec<String> = checks
+            .iter()
+            .map(|check| {
+                format!(
+                    "{}: {}",
+                    check.encoder,
+                    check.error.as_deref().unwrap_or("unknown error")
+                )
+            })
+            .collect();
+        return Err(format!(
+            "No AV1 encoder passed its test encode. {}",
+            reasons.join("; ")
+        ));
+    }
+    Ok(usable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::usable_encoders;
+    use crate::model::EncoderCheck;
+
+    fn check(encoder: &str, error: Option<&str>) -> EncoderCheck {
+        EncoderCheck {
+            encoder: encoder.to_string(),
+            usable: error.is_none(),
+            error: error.map(str::to_string),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn usable_encoders_drops_encoders_that_failed_the_test_encode() {
+        let checks = vec![
+            check("av1_nvenc", Some("Cannot load nvcuda.dll")),
+            check("libaom-av1", None),
+            check("libsvtav1", None),
+        ];
+
+        assert_eq!(
+            usable_encoders(&checks, None),
+            Ok(vec!["libaom-av1".to_string(), "libsvtav1".to_string()])
+        );
+        let error = usable_encoders(&checks, Some("av1_nvenc"))
+            .expect_err("failed requested encoder should be refused");
+        assert!(error.contains("Cannot load nvcuda.dll"));
+    }
+
+    #[test]
+    fn usable_encoders_reports_every_failure_when_none_pass() {
+        let checks = vec![
+            check("av1_nvenc", Some("Cannot load nvcuda.dll")),
+            check("av1_qsv", Some("No device available")),
+        ];
+
+        let error = usable_encoders(&checks, None).expect_err("no encoder should pass");
+        assert!(error.contains("av1_nvenc: Cannot load nvcuda.dll"));
+        assert!(error.contains("av1_qsv: No device available"));
+    }
+}
@@ -1,9 +1,15 @@
 pub const ERROR_PREFIX: &str = "VID2AV1_ERROR";
 
 pub const ERROR_CODE_CANCELED_BY_USER: &str = "CANCELED_BY_USER";
+pub const ERROR_CODE_SKIPPED_BY_USER: &str = "SKIPPED_BY_USER";
+pub const ERROR_CODE_STOPPED_BY_USER: &str = "STOPPED_BY_USER";
 pub const ERROR_CODE_NO_ACTIVE_CONVERSION: &str = "NO_ACTIVE_CONVERSION";
+pub const ERROR_CODE_OUTPUT_EXISTS: &str = "OUTPUT_EXISTS";
+pub const ERROR_CODE_TARGET_NOT_MET: &str = "TARGET_NOT_MET";
 
 const CANCELED_BY_USER_MESSAGE: &str = "Conversion canceled by user.";
+const SKIPPED_BY_USER_MESSAGE: &str = "File skipped by user.";
+const STOPPED_BY_USER_MESSAGE: &str = "Queue stopped by user before this file started.";
 const NO_ACTIVE_CONVERSION_MESSAGE: &str = "No conversion is currently running.";
 
 pub fn encode_error(code: &str, message: &str) -> String {
@@ -14,6 +20,14 @@ pub fn error_canceled_by_user() -> String {
     encode_error(ERROR_CODE_CANCELED_BY_USER, CANCELED_BY_USER_MESSAGE)
 }
 
+pub fn error_skipped_by_user() -> String {
+    encode_error(ERROR_CODE_SKIPPED_BY_USER, SKIPPED_BY_USER_MESSAGE)
+}
+
+pub fn error_stopped_by_user() -> String {
+    encode_error(ERROR_CODE_STOPPED_BY_USER, STOPPED_BY_USER_MESSAGE)
+}
+
 pub fn error_no_active_conversion() -> String {
     encode_error(
         ERROR_CODE_NO_ACTIVE_CONVERSION,
@@ -21,6 +35,27 @@ pub fn error_no_active_conversion() -> String {
     )
 }
 
+pub fn error_output_exists(output_path: &str) -> String {
+    encode_error(
+        ERROR_CODE_OUTPUT_EXISTS,
+        &format!("Output already exists: {output_path}"),
+    )
+}
+
+pub fn error_target_not_met(
+    attempts: u32,
+    output_size_bytes: u64,
+    target_size_bytes: u64,
+) -> String {
+    let attempt_noun = if attempts == 1 { "attempt" } else { "attempts" };
+    encode_error(
+        ERROR_CODE_TARGET_NOT_MET,
+        &format!(
+            "Target size not met after {attempts} {attempt_noun}: the last output was {output_size_bytes} bytes, the target is {target_size_bytes} bytes."
+        ),
+    )
+}
+
 pub fn is_error_code(error: &str, code: &str) -> bool {
     let mut parts = error.splitn(3, '|');
     matches!(
@@ -28,3 +63,14 @@ pub fn is_error_code(error: &str, code: &str) -> bool {
         (Some(prefix), Some(found_code), Some(_)) if prefix == ERROR_PREFIX && found_code == code
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::error_target_not_met;
+
+    #[test]
+    fn target_not_met_counts_attempts_in_words() {
+        assert!(error_target_not_met(1, 2_000, 1_000).contains("after 1 attempt:"));
+        assert!(error_target_not_met(3, 2_000, 1_000).contains("after 3 attempts:"));
+    }
+}
@@ -0,0 +1,32 @@
+use super::{option_max, scale_quality, Av1EncoderBackend};
+use crate::ffmpeg::EncoderDescription;
+
+const MAX_QP: u32 = 255;
+
+pub struct Av1Amf;
+
+impl Av1EncoderBackend for Av1Amf {
+    fn name(&self) -> &'static str {
+        "av1_amf"
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(&self, quality_level: u32, description: Option<&EncoderDescription>) -> Vec<String> {
+        let qp = scale_quality(quality_level, option_max(description, "qp_i", MAX_QP)).to_string();
+        vec![
+            "-rc".to_string(),
+            "cqp".to_string(),
+            "-qp_i".to_string(),
+            qp.clone(),
+            "-qp_p".to_string(),
+            qp,
+        ]
+    }
+
+    fn device_errors(&self) -> &'static [&'static str] {
+        &["amfrt64", "amf failed", "failed to initialize amf"]
+    }
+}
@@ -0,0 +1,33 @@
+use super::{scale_quality, Av1EncoderBackend};
+use crate::ffmpeg::EncoderDescription;
+
+const MAX_QUALITY: u32 = 100;
+
+pub struct Av1Mf;
+
+impl Av1EncoderBackend for Av1Mf {
+    fn name(&self) -> &'static str {
+        "av1_mf"
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(
+        &self,
+        quality_level: u32,
+        _description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        vec![
+            "-rate_control".to_string(),
+            "quality".to_string(),
+            "-quality".to_string(),
+            (MAX_QUALITY - scale_quality(quality_level, MAX_QUALITY)).to_string(),
+        ]
+    }
+
+    fn device_errors(&self) -> &'static [&'static str] {
+        &["could not find any mft"]
+    }
+}
@@ -0,0 +1,45 @@
+use super::{option_max, scale_quality, Av1EncoderBackend, PresetValues};
+use crate::ffmpeg::EncoderDescription;
+
+const MAX_CQ: u32 = 51;
+
+pub struct Av1Nvenc;
+
+impl Av1EncoderBackend for Av1Nvenc {
+    fn name(&self) -> &'static str {
+        "av1_nvenc"
+    }
+
+    fn cbr_switch(&self) -> Option<(&'static str, &'static str)> {
+        Some(("rc", "cbr"))
+    }
+
+    fn preset_option(&self) -> Option<(&'static str, PresetValues)> {
+        Some(("preset", ["p1", "p3", "p4", "p6", "p7"]))
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(&self, quality_level: u32, description: Option<&EncoderDescription>) -> Vec<String> {
+        let cq = scale_quality(quality_level, option_max(description, "cq", MAX_CQ)).max(1);
+        vec![
+            "-rc".to_string(),
+            "vbr".to_string(),
+            "-cq".to_string(),
+            cq.to_string(),
+            "-b:v".to_string(),
+            "0".to_string(),
+        ]
+    }
+
+    fn device_errors(&self) -> &'static [&'static str] {
+        &[
+            "openencodesessionex failed",
+            "nvcuda",
+            "no nvenc capable devices",
+            "driver does not support the required nvenc api version",
+        ]
+    }
+}
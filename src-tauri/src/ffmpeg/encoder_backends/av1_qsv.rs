@@ -0,0 +1,29 @@
+use super::{scale_quality, Av1EncoderBackend};
+use crate::ffmpeg::EncoderDescription;
+
+const MAX_GLOBAL_QUALITY: u32 = 255;
+
+pub struct Av1Qsv;
+
+impl Av1EncoderBackend for Av1Qsv {
+    fn name(&self) -> &'static str {
+        "av1_qsv"
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(
+        &self,
+        quality_level: u32,
+        _description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        let quality = scale_quality(quality_level, MAX_GLOBAL_QUALITY).max(1);
+        vec!["-global_quality".to_string(), quality.to_string()]
+    }
+
+    fn device_errors(&self) -> &'static [&'static str] {
+        &["mfx session", "mfxinit", "no qsv device"]
+    }
+}
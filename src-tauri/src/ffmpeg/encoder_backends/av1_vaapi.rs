@@ -0,0 +1,30 @@
+use super::{option_max, scale_quality, Av1EncoderBackend};
+use crate::ffmpeg::EncoderDescription;
+
+const MAX_QP: u32 = 255;
+
+pub struct Av1Vaapi;
+
+impl Av1EncoderBackend for Av1Vaapi {
+    fn name(&self) -> &'static str {
+        "av1_vaapi"
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(&self, quality_level: u32, description: Option<&EncoderDescription>) -> Vec<String> {
+        let qp = scale_quality(quality_level, option_max(description, "qp", MAX_QP));
+        vec![
+            "-rc_mode".to_string(),
+            "CQP".to_string(),
+            "-qp".to_string(),
+            qp.to_string(),
+        ]
+    }
+
+    fn device_errors(&self) -> &'static [&'static str] {
+        &["failed to initialise vaapi", "no va display"]
+    }
+}
@@ -0,0 +1,9 @@
+use super::Av1EncoderBackend;
+
+pub struct Generic;
+
+impl Av1EncoderBackend for Generic {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+}
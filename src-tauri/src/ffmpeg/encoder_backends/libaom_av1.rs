@@ -0,0 +1,35 @@
+use super::{Av1EncoderBackend, PresetValues, MAX_QUALITY_LEVEL};
+use crate::ffmpeg::EncoderDescription;
+
+pub struct LibAomAv1;
+
+impl Av1EncoderBackend for LibAomAv1 {
+    fn name(&self) -> &'static str {
+        "libaom-av1"
+    }
+
+    fn preset_option(&self) -> Option<(&'static str, PresetValues)> {
+        Some(("cpu-used", ["8", "6", "4", "2", "1"]))
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(
+        &self,
+        quality_level: u32,
+        _description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        vec![
+            "-crf".to_string(),
+            quality_level.min(MAX_QUALITY_LEVEL).to_string(),
+            "-b:v".to_string(),
+            "0".to_string(),
+        ]
+    }
+
+    fn supports_two_pass(&self) -> bool {
+        true
+    }
+}
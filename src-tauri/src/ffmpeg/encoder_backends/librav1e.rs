@@ -0,0 +1,29 @@
+use super::{option_max, scale_quality, Av1EncoderBackend, PresetValues};
+use crate::ffmpeg::EncoderDescription;
+
+const MAX_QP: u32 = 255;
+
+pub struct LibRav1e;
+
+impl Av1EncoderBackend for LibRav1e {
+    fn name(&self) -> &'static str {
+        "librav1e"
+    }
+
+    fn preset_option(&self) -> Option<(&'static str, PresetValues)> {
+        Some(("speed", ["10", "8", "6", "4", "2"]))
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(&self, quality_level: u32, description: Option<&EncoderDescription>) -> Vec<String> {
+        let qp = scale_quality(quality_level, option_max(description, "qp", MAX_QP));
+        vec!["-qp".to_string(), qp.to_string()]
+    }
+
+    fn supports_two_pass(&self) -> bool {
+        true
+    }
+}
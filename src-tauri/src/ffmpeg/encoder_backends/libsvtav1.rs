@@ -0,0 +1,29 @@
+use super::{Av1EncoderBackend, PresetValues, MAX_QUALITY_LEVEL};
+use crate::ffmpeg::EncoderDescription;
+
+pub struct LibSvtAv1;
+
+impl Av1EncoderBackend for LibSvtAv1 {
+    fn name(&self) -> &'static str {
+        "libsvtav1"
+    }
+
+    fn preset_option(&self) -> Option<(&'static str, PresetValues)> {
+        Some(("preset", ["12", "10", "8", "6", "4"]))
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        true
+    }
+
+    fn cq_args(
+        &self,
+        quality_level: u32,
+        _description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        vec![
+            "-crf".to_string(),
+            quality_level.min(MAX_QUALITY_LEVEL).to_string(),
+        ]
+    }
+}
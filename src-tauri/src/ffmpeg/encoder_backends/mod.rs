@@ -0,0 +1,316 @@
+mod av1_amf;
+mod av1_mf;
+mod av1_nvenc;
+mod av1_qsv;
+mod av1_vaapi;
+mod generic;
+mod libaom_av1;
+mod librav1e;
+mod libsvtav1;
+
+use std::fmt;
+
+use super::encoder_help::{EncoderDescription, RateControlMode};
+
+pub const MAX_QUALITY_LEVEL: u32 = 63;
+pub const MIN_PRESET_LEVEL: u32 = 1;
+pub const MAX_PRESET_LEVEL: u32 = 5;
+
+const MIN_BUFFER_KBPS: u32 = 1000;
+const DEVICE_ERRORS: &[&str] = &[
+    "cannot load",
+    "no device",
+    "device not found",
+    "no capable devices",
+    "failed to create device",
+    "device creation failed",
+];
+const SETTINGS_ERRORS: &[&str] = &[
+    "option not found",
+    "unrecognized option",
+    "not supported",
+    "invalid argument",
+    "error setting option",
+];
+
+static BACKENDS: &[&dyn Av1EncoderBackend] = &[
+    &av1_nvenc::Av1Nvenc,
+    &libsvtav1::LibSvtAv1,
+    &libaom_av1::LibAomAv1,
+    &librav1e::LibRav1e,
+    &av1_qsv::Av1Qsv,
+    &av1_amf::Av1Amf,
+    &av1_mf::Av1Mf,
+    &av1_vaapi::Av1Vaapi,
+];
+
+pub type PresetValues = [&'static str; (MAX_PRESET_LEVEL - MIN_PRESET_LEVEL + 1) as usize];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderFailure {
+    DeviceUnavailable,
+    UnsupportedSettings,
+    Other,
+}
+
+impl fmt::Display for EncoderFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DeviceUnavailable => write!(f, "no usable device"),
+            Self::UnsupportedSettings => write!(f, "unsupported settings"),
+            Self::Other => write!(f, "encode failed"),
+        }
+    }
+}
+
+pub trait Av1EncoderBackend: Sync {
+    fn name(&self) -> &'static str;
+
+    fn cbr_switch(&self) -> Option<(&'static str, &'static str)> {
+        None
+    }
+
+    fn vbr_args(&self, video_bitrate_kbps: u32) -> Vec<String> {
+        vec!["-b:v".to_string(), format!("{video_bitrate_kbps}k")]
+    }
+
+    fn cbr_args(
+        &self,
+        video_bitrate_kbps: u32,
+        description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        let mut args = self.vbr_args(video_bitrate_kbps);
+        let switch = match description {
+            Some(description) => {
+                description.rate_control_selector(RateControlMode::ConstantBitrate)
+            }
+            None => self.cbr_switch(),
+        };
+        if let Some((selector, value)) = switch {
+            args.push(format!("-{selector}"));
+            args.push(value.to_string());
+        }
+
+        let buf = (video_bitrate_kbps * 2).max(MIN_BUFFER_KBPS);
+        args.extend([
+            "-minrate".to_string(),
+            format!("{video_bitrate_kbps}k"),
+            "-maxrate".to_string(),
+            format!("{video_bitrate_kbps}k"),
+            "-bufsize".to_string(),
+            format!("{buf}k"),
+        ]);
+        args
+    }
+
+    fn preset_option(&self) -> Option<(&'static str, PresetValues)> {
+        None
+    }
+
+    fn preset_args(
+        &self,
+        preset_level: u32,
+        description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        let Some((option, values)) = self.preset_option() else {
+            return Vec::new();
+        };
+        if description.is_some_and(|description| description.option(option).is_none()) {
+            return Vec::new();
+        }
+        let index = preset_level.clamp(MIN_PRESET_LEVEL, MAX_PRESET_LEVEL) - MIN_PRESET_LEVEL;
+        vec![format!("-{option}"), values[index as usize].to_string()]
+    }
+
+    fn supports_constant_quality(&self) -> bool {
+        false
+    }
+
+    fn cq_args(
+        &self,
+        quality_level: u32,
+        _description: Option<&EncoderDescription>,
+    ) -> Vec<String> {
+        vec!["-crf".to_string(), quality_level.to_string()]
+    }
+
+    fn supports_two_pass(&self) -> bool {
+        false
+    }
+
+    fn device_errors(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn classify_error(&self, error: &str) -> EncoderFailure {
+        let error = error.to_lowercase();
+        let matches = |patterns: &[&str]| patterns.iter().any(|pattern| error.contains(pattern));
+        if matches(self.device_errors()) || matches(DEVICE_ERRORS) {
+            EncoderFailure::DeviceUnavailable
+        } else if matches(SETTINGS_ERRORS) {
+            EncoderFailure::UnsupportedSettings
+        } else {
+            EncoderFailure::Other
+        }
+    }
+}
+
+pub fn encoder_backend(encoder: &str) -> &'static dyn Av1EncoderBackend {
+    BACKENDS
+        .iter()
+        .copied()
+        .find(|backend| backend.name() == encoder)
+        .unwrap_or(&generic::Generic)
+}
+
+pub fn registered_encoders() -> impl Iterator<Item = &'static str> {
+    BACKENDS.iter().map(|backend| backend.name())
+}
+
+fn scale_quality(quality_level: u32, max: u32) -> u32 {
+    let level = quality_level.min(MAX_QUALITY_LEVEL);
+    (level * max + MAX_QUALITY_LEVEL / 2) / MAX_QUALITY_LEVEL
+}
+
+fn option_max(description: Option<&EncoderDescription>, name: &str, fallback: u32) -> u32 {
+    description
+        .and_then(|description| description.option(name))
+        .and_then(|option| option.max)
+        .map_or(fallback, |max| (max as u32).min(fallback))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encoder_backend, registered_encoders, EncoderFailure};
+    use crate::ffmpeg::encoder_help::parse_encoder_help;
+
+    const PRESET_TABLE: &[(&str, [&[&str]; 5])] = &[
+        (
+            "av1_nvenc",
+            [
+                &["-preset", "p1"],
+                &["-preset", "p3"],
+                &["-preset", "p4"],
+                &["-preset", "p6"],
+                &["-preset", "p7"],
+            ],
+        ),
+        (
+            "libsvtav1",
+            [
+                &["-preset", "12"],
+                &["-preset", "10"],
+                &["-preset", "8"],
+                &["-preset", "6"],
+                &["-preset", "4"],
+            ],
+        ),
+        (
+            "libaom-av1",
+            [
+                &["-cpu-used", "8"],
+                &["-cpu-used", "6"],
+                &["-cpu-used", "4"],
+                &["-cpu-used", "2"],
+                &["-cpu-used", "1"],
+            ],
+        ),
+        (
+            "librav1e",
+            [
+                &["-speed", "10"],
+                &["-speed", "8"],
+                &["-speed", "6"],
+                &["-speed", "4"],
+                &["-speed", "2"],
+            ],
+        ),
+        ("av1_qsv", [&[], &[], &[], &[], &[]]),
+        ("av1_amf", [&[], &[], &[], &[], &[]]),
+        ("av1_mf", [&[], &[], &[], &[], &[]]),
+        ("av1_vaapi", [&[], &[], &[], &[], &[]]),
+    ];
+
+    #[test]
+    fn preset_levels_map_to_each_encoder_speed_knob() {
+        assert_eq!(
+            PRESET_TABLE
+                .iter()
+                .map(|(encoder, _)| *encoder)
+                .collect::<Vec<_>>(),
+            registered_encoders().collect::<Vec<_>>(),
+            "every registered encoder needs a preset table row"
+        );
+        for (encoder, expected) in PRESET_TABLE {
+            for (level, expected_args) in (1..).zip(expected) {
+                assert_eq!(
+                    encoder_backend(encoder).preset_args(level, None),
+                    *expected_args,
+                    "{encoder} at preset level {level}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn preset_args_respect_the_introspected_options() {
+        let svt = parse_encoder_help(include_str!("../fixtures/help_libsvtav1.txt"))
+            .expect("svt help should parse");
+        let old_rav1e = parse_encoder_help(
+            "Encoder librav1e [librav1e AV1]:\nlibrav1e AVOptions:\n  -qp                <int>        E..V....... use constant quantizer mode (from -1 to 255) (default -1)\n",
+        )
+        .expect("rav1e help should parse");
+
+        assert_eq!(
+            encoder_backend("libsvtav1").preset_args(5, Some(&svt)),
+            ["-preset", "4"]
+        );
+        assert!(encoder_backend("librav1e")
+            .preset_args(3, Some(&old_rav1e))
+            .is_empty());
+        assert!(encoder_backend("my_custom_av1")
+            .preset_args(3, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn registry_keeps_preference_order_and_falls_back_to_generic() {
+        assert_eq!(
+            registered_encoders().collect::<Vec<_>>(),
+            [
+                "av1_nvenc",
+                "libsvtav1",
+                "libaom-av1",
+                "librav1e",
+                "av1_qsv",
+                "av1_amf",
+                "av1_mf",
+                "av1_vaapi"
+            ]
+        );
+        assert_eq!(encoder_backend("libaom-av1").name(), "libaom-av1");
+        assert!(!encoder_backend("my_custom_av1").supports_constant_quality());
+    }
+
+    #[test]
+    fn classify_error_uses_encoder_specific_device_messages() {
+        let nvenc = encoder_backend("av1_nvenc");
+        assert_eq!(
+            nvenc
+                .classify_error("[av1_nvenc @ 0x1] OpenEncodeSessionEx failed: out of memory (10)"),
+            EncoderFailure::DeviceUnavailable
+        );
+        assert_eq!(
+            encoder_backend("av1_qsv").classify_error("Error creating a MFX session: -9."),
+            EncoderFailure::DeviceUnavailable
+        );
+        assert_eq!(
+            encoder_backend("libsvtav1").classify_error("Unrecognized option 'foo'."),
+            EncoderFailure::UnsupportedSettings
+        );
+        assert_eq!(
+            encoder_backend("libsvtav1").classify_error("Conversion failed!"),
+            EncoderFailure::Other
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::command::hidden_command;
+
+const RATE_CONTROL_SELECTORS: &[&str] = &["rc", "rc_mode", "rate_control"];
+const CONSTANT_QUALITY_VALUES: &[&str] = &["constqp", "cqp", "quality", "icq", "qvbr"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RateControlMode {
+    ConstantBitrate,
+    VariableBitrate,
+    ConstantQuality,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderDescription {
+    pub name: String,
+    pub options: Vec<EncoderOption>,
+    pub rate_control_modes: Vec<RateControlMode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderOption {
+    pub name: String,
+    pub value_type: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub values: Vec<String>,
+}
+
+impl EncoderDescription {
+    pub fn option(&self, name: &str) -> Option<&EncoderOption> {
+        self.options.iter().find(|option| option.name == name)
+    }
+
+    pub fn supports_rate_control(&self, mode: RateControlMode) -> bool {
+        self.rate_control_modes.contains(&mode)
+    }
+
+    pub fn rate_control_selector(&self, mode: RateControlMode) -> Option<(&str, &str)> {
+        let selector = self.selector()?;
+        selector
+            .values
+            .iter()
+            .find(|value| rate_control_mode(value) == Some(mode))
+            .map(|value| (selector.name.as_str(), value.as_str()))
+    }
+
+    fn selector(&self) -> Option<&EncoderOption> {
+        RATE_CONTROL_SELECTORS
+            .iter()
+            .find_map(|name| self.option(name))
+            .filter(|option| !option.values.is_empty())
+    }
+}
+
+pub fn describe_encoder(ffmpeg_path: &Path, encoder: &str) -> Result<EncoderDescription, String> {
+    let output = hidden_command(ffmpeg_path)
+        .args(["-hide_banner", "-h", &format!("encoder={encoder}")])
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg -h encoder={encoder}: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg -h encoder={encoder} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    parse_encoder_help(&String::from_utf8_lossy(&output.stdout))
+}
+
+pub fn parse_encoder_help(text: &str) -> Result<EncoderDescription, String> {
+    let mut name = None;
+    let mut options: Vec<EncoderOption> = Vec::new();
+    let mut in_options = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix("Encoder ") {
+            name = header.split_whitespace().next().map(str::to_string);
+        } else if trimmed.ends_with("AVOptions:") {
+            in_options = true;
+        } else if !in_options || trimmed.is_empty() {
+            continue;
+        } else if let Some(option_line) = trimmed.strip_prefix('-') {
+            options.push(parse_option_line(option_line));
+        } else if let Some(option) = options.last_mut() {
+            if let Some(value) = trimmed.split_whitespace().next() {
+                option.values.push(value.to_string());
+            }
+        }
+    }
+
+    let name = name.ok_or_else(|| {
+        "ffmpeg encoder help did not name the encoder; it may not exist.".to_string()
+    })?;
+    let mut description = EncoderDescription {
+        name,
+        options,
+        rate_control_modes: Vec::new(),
+    };
+    description.rate_control_modes = rate_control_modes(&description);
+    Ok(description)
+}
+
+fn parse_option_line(line: &str) -> EncoderOption {
+    let mut tokens = line.split_whitespace();
+    let name = tokens.next().unwrap_or_default().to_string();
+    let value_type = tokens
+        .next()
+        .map(|token| token.trim_start_matches('<').trim_end_matches('>'))
+        .unwrap_or_default()
+        .to_string();
+    let (min, max) = line
+        .rfind("(from ")
+        .and_then(|start| {
+            let range = &line[start + "(from ".len()..];
+            let (min, rest) = range.split_once(" to ")?;
+            let max = rest.split(')').next()?;
+            Some((parse_bound(min), parse_bound(max)))
+        })
+        .unwrap_or((None, None));
+
+    EncoderOption {
+        name,
+        value_type,
+        min,
+        max,
+        values: Vec::new(),
+    }
+}
+
+fn parse_bound(text: &str) -> Option<f64> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && value.abs() < i32::MAX as f64)
+}
+
+fn rate_control_mode(value: &str) -> Option<RateControlMode> {
+    let value = value.to_ascii_lowercase();
+    if CONSTANT_QUALITY_VALUES.contains(&value.as_str()) {
+        Some(RateControlMode::ConstantQuality)
+    } else if value.contains("cbr") {
+        Some(RateControlMode::ConstantBitrate)
+    } else if value.contains("vbr") {
+        Some(RateControlMode::VariableBitrate)
+    } else {
+        None
+    }
+}
+
+fn rate_control_modes(description: &EncoderDescription) -> Vec<RateControlMode> {
+    let Some(selector) = description.selector() else {
+        return vec![
+            RateControlMode::ConstantBitrate,
+            RateControlMode::VariableBitrate,
+            RateControlMode::ConstantQuality,
+        ];
+    };
+
+    let mut modes = Vec::new();
+    for mode in selector
+        .values
+        .iter()
+        .filter_map(|value| rate_control_mode(value))
+    {
+        if !modes.contains(&mode) {
+            modes.push(mode);
+        }
+    }
+    modes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_encoder_help, RateControlMode};
+
+    const AV1_NVENC_HELP: &str = include_str!("fixtures/help_av1_nvenc.txt");
+    const LIBSVTAV1_HELP: &str = include_str!("fixtures/help_libsvtav1.txt");
+    const AV1_VAAPI_HELP: &str = include_str!("fixtures/help_av1_vaapi.txt");
+
+    #[test]
+    fn parse_encoder_help_reads_nvenc_options_and_modes() {
+        let description = parse_encoder_help(AV1_NVENC_HELP).expect("nvenc help should parse");
+
+        assert_eq!(description.name, "av1_nvenc");
+        let cq = description.option("cq").expect("cq option should exist");
+        assert_eq!(
+            (cq.value_type.as_str(), cq.min, cq.max),
+            ("float", Some(0.0), Some(51.0))
+        );
+        let rc = description.option("rc").expect("rc option should exist");
+        assert_eq!(rc.min, Some(-1.0));
+        assert_eq!(rc.max, None);
+        assert_eq!(rc.values, ["constqp", "vbr", "cbr"]);
+        assert_eq!(
+            description
+                .option("preset")
+                .map(|preset| preset.values.len()),
+            Some(11)
+        );
+        assert_eq!(
+            description.rate_control_modes,
+            [
+                RateControlMode::ConstantQuality,
+                RateControlMode::VariableBitrate,
+                RateControlMode::ConstantBitrate
+            ]
+        );
+        assert_eq!(
+            description.rate_control_selector(RateControlMode::ConstantBitrate),
+            Some(("rc", "cbr"))
+        );
+    }
+
+    #[test]
+    fn parse_encoder_help_reads_software_encoder_without_selector() {
+        let description = parse_encoder_help(LIBSVTAV1_HELP).expect("svt help should parse");
+
+        assert_eq!(description.name, "libsvtav1");
+        let preset = description
+            .option("preset")
+            .expect("preset option should exist");
+        assert_eq!((preset.min, preset.max), (Some(-2.0), Some(13.0)));
+        assert!(description
+            .option("svtav1-params")
+            .is_some_and(|option| { option.value_type == "dictionary" && option.min.is_none() }));
+        assert!(description.supports_rate_control(RateControlMode::ConstantBitrate));
+        assert!(description.supports_rate_control(RateControlMode::ConstantQuality));
+        assert_eq!(
+            description.rate_control_selector(RateControlMode::ConstantBitrate),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_encoder_help_maps_vaapi_rate_control_names() {
+        let description = parse_encoder_help(AV1_VAAPI_HELP).expect("vaapi help should parse");
+
+        assert_eq!(
+            description.rate_control_selector(RateControlMode::ConstantBitrate),
+            Some(("rc_mode", "CBR"))
+        );
+        assert_eq!(
+            description.rate_control_selector(RateControlMode::ConstantQuality),
+            Some(("rc_mode", "CQP"))
+        );
+        assert_eq!(
+            description.rate_control_selector(RateControlMode::VariableBitrate),
+            Some(("rc_mode", "VBR"))
+        );
+    }
+
+    #[test]
+    fn parse_encoder_help_rejects_unknown_encoder() {
+        let error = parse_encoder_help("Codec 'av1_fake' is not recognized by FFmpeg.\n")
+            .expect_err("unknown encoder should fail");
+        assert!(error.contains("did not name the encoder"));
+    }
+}
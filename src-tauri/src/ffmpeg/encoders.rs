@@ -2,17 +2,7 @@ use std::collections::{BTreeSet, HashSet};
 use std::path::Path;
 
 use super::command::hidden_command;
-
-pub const PREFERRED_ENCODER_ORDER: &[&str] = &[
-    "av1_nvenc",
-    "libsvtav1",
-    "libaom-av1",
-    "librav1e",
-    "av1_qsv",
-    "av1_amf",
-    "av1_mf",
-    "av1_vaapi",
-];
+use super::encoder_backends::registered_encoders;
 
 pub fn list_encoders(ffmpeg_path: &Path) -> Result<Vec<String>, String> {
     let output = hidden_command(ffmpeg_path)
@@ -57,10 +47,8 @@ pub fn resolve_encoder_candidates(
     }
 
     let available_set: HashSet<&str> = available_all.iter().map(String::as_str).collect();
-    let preferred_available = PREFERRED_ENCODER_ORDER
-        .iter()
-        .copied()
-        .filter(|encoder| available_set.contains(*encoder));
+    let preferred_available =
+        registered_encoders().filter(|encoder| available_set.contains(*encoder));
 
     let mut ordered = Vec::new();
     let mut included = HashSet::new();
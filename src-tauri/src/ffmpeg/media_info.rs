@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaInfo {
+    pub format_name: String,
+    pub duration_sec: f64,
+    pub size_bytes: Option<u64>,
+    pub bit_rate_kbps: Option<u32>,
+    pub streams: Vec<StreamInfo>,
+    pub chapters: Vec<ChapterInfo>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamKind {
+    Video,
+    Audio,
+    Subtitle,
+    Data,
+    Attachment,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamInfo {
+    pub index: u32,
+    pub kind: StreamKind,
+    pub codec_name: String,
+    pub profile: Option<String>,
+    pub bit_rate_kbps: Option<u32>,
+    pub frame_count: Option<u64>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub disposition: Disposition,
+    pub video: Option<VideoInfo>,
+    pub audio: Option<AudioInfo>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Disposition {
+    pub default: bool,
+    pub forced: bool,
+    pub dub: bool,
+    pub original: bool,
+    pub comment: bool,
+    pub hearing_impaired: bool,
+    pub visual_impaired: bool,
+    pub attached_pic: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoInfo {
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: Option<f64>,
+    pub pixel_format: Option<String>,
+    pub color: ColorInfo,
+    pub hdr: bool,
+    pub rotation_deg: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorInfo {
+    pub range: Option<String>,
+    pub space: Option<String>,
+    pub transfer: Option<String>,
+    pub primaries: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioInfo {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub channel_layout: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterInfo {
+    pub start_sec: f64,
+    pub end_sec: f64,
+    pub title: Option<String>,
+}
+
+impl MediaInfo {
+    pub fn streams_of(&self, kind: StreamKind) -> impl Iterator<Item = &StreamInfo> {
+        self.streams
+            .iter()
+            .filter(move |stream| stream.kind == kind)
+    }
+
+    pub fn primary_video(&self) -> Option<&StreamInfo> {
+        self.streams_of(StreamKind::Video)
+            .find(|stream| !stream.disposition.attached_pic)
+    }
+}
+
+impl ColorInfo {
+    pub fn is_hdr(&self) -> bool {
+        matches!(
+            self.transfer.as_deref(),
+            Some("smpte2084") | Some("arib-std-b67")
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct RawProbe {
+    #[serde(default)]
+    format: Option<RawFormat>,
+    #[serde(default)]
+    streams: Vec<RawStream>,
+    #[serde(default)]
+    chapters: Vec<RawChapter>,
+}
+
+#[derive(Deserialize)]
+struct RawFormat {
+    #[serde(default)]
+    format_name: Option<String>,
+    #[serde(default)]
+    duration: Option<String>,
+    #[serde(default)]
+    size: Option<String>,
+    #[serde(default)]
+    bit_rate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawStream {
+    index: u32,
+    #[serde(default)]
+    codec_type: Option<String>,
+    #[serde(default)]
+    codec_name: Option<String>,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    bit_rate: Option<String>,
+    #[serde(default)]
+    nb_frames: Option<String>,
+    #[serde(default)]
+    width: Option<u32>,
+    #[serde(default)]
+    height: Option<u32>,
+    #[serde(default)]
+    avg_frame_rate: Option<String>,
+    #[serde(default)]
+    r_frame_rate: Option<String>,
+    #[serde(default)]
+    pix_fmt: Option<String>,
+    #[serde(default)]
+    color_range: Option<String>,
+    #[serde(default)]
+    color_space: Option<String>,
+    #[serde(default)]
+    color_transfer: Option<String>,
+    #[serde(default)]
+    color_primaries: Option<String>,
+    #[serde(default)]
+    sample_rate: Option<String>,
+    #[serde(default)]
+    channels: Option<u32>,
+    #[serde(default)]
+    channel_layout: Option<String>,
+    #[serde(default)]
+    disposition: HashMap<String, i64>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    side_data_list: Vec<RawSideData>,
+}
+
+#[derive(Deserialize)]
+struct RawSideData {
+    #[serde(default)]
+    rotation: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawChapter {
+    #[serde(default)]
+    start_time: Option<String>,
+    #[serde(default)]
+    end_time: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+pub fn parse_media_info(json: &str) -> Result<MediaInfo, String> {
+    let raw: RawProbe =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse ffprobe output: {e}"))?;
+    let format = raw
+        .format
+        .ok_or_else(|| "Expected ffprobe format output.".to_string())?;
+
+    let duration_sec = format
+        .duration
+        .as_deref()
+        .and_then(parse_number::<f64>)
+        .ok_or_else(|| "Expected ffprobe duration output.".to_string())?;
+    if duration_sec <= 0.0 {
+        return Err("Duration must be > 0.".to_string());
+    }
+
+    Ok(MediaInfo {
+        format_name: format.format_name.unwrap_or_default(),
+        duration_sec,
+        size_bytes: format.size.as_deref().and_then(parse_number),
+        bit_rate_kbps: format.bit_rate.as_deref().and_then(parse_kbps),
+        streams: raw.streams.into_iter().map(convert_stream).collect(),
+        chapters: raw.chapters.into_iter().map(convert_chapter).collect(),
+    })
+}
+
+fn convert_stream(raw: RawStream) -> StreamInfo {
+    let kind = match raw.codec_type.as_deref() {
+        Some("video") => StreamKind::Video,
+        Some("audio") => StreamKind::Audio,
+        Some("subtitle") => StreamKind::Subtitle,
+        Some("data") => StreamKind::Data,
+        Some("attachment") => StreamKind::Attachment,
+        _ => StreamKind::Unknown,
+    };
+
+    let video = (kind == StreamKind::Video).then(|| {
+        let color = ColorInfo {
+            range: known_value(raw.color_range.clone()),
+            space: known_value(raw.color_space.clone()),
+            transfer: known_value(raw.color_transfer.clone()),
+            primaries: known_value(raw.color_primaries.clone()),
+        };
+        VideoInfo {
+            width: raw.width.unwrap_or_default(),
+            height: raw.height.unwrap_or_default(),
+            frame_rate: raw
+                .avg_frame_rate
+                .as_deref()
+                .and_then(parse_rational)
+                .or_else(|| raw.r_frame_rate.as_deref().and_then(parse_rational)),
+            pixel_format: raw.pix_fmt.clone(),
+            hdr: color.is_hdr(),
+            color,
+            rotation_deg: rotation_deg(&raw),
+        }
+    });
+
+    let audio = (kind == StreamKind::Audio).then(|| AudioInfo {
+        sample_rate: raw.sample_rate.as_deref().and_then(parse_number),
+        channels: raw.channels,
+        channel_layout: raw.channel_layout.clone(),
+    });
+
+    let flag = |name: &str| raw.disposition.get(name).is_some_and(|value| *value != 0);
+    let disposition = Disposition {
+        default: flag("default"),
+        forced: flag("forced"),
+        dub: flag("dub"),
+        original: flag("original"),
+        comment: flag("comment"),
+        hearing_impaired: flag("hearing_impaired"),
+        visual_impaired: flag("visual_impaired"),
+        attached_pic: flag("attached_pic"),
+    };
+
+    StreamInfo {
+        index: raw.index,
+        kind,
+        codec_name: raw.codec_name.unwrap_or_default(),
+        profile: raw.profile,
+        bit_rate_kbps: raw.bit_rate.as_deref().and_then(parse_kbps),
+        frame_count: raw
+            .nb_frames
+            .as_deref()
+            .and_then(parse_number::<u64>)
+            .filter(|count| *count > 0),
+        language: known_value(raw.tags.get("language").cloned()),
+        title: raw.tags.get("title").cloned(),
+        disposition,
+        video,
+        audio,
+    }
+}
+
+fn convert_chapter(raw: RawChapter) -> ChapterInfo {
+    ChapterInfo {
+        start_sec: raw
+            .start_time
+            .as_deref()
+            .and_then(parse_number)
+            .unwrap_or_default(),
+        end_sec: raw
+            .end_time
+            .as_deref()
+            .and_then(parse_number)
+            .unwrap_or_default(),
+        title: raw.tags.get("title").cloned(),
+    }
+}
+
+fn rotation_deg(raw: &RawStream) -> u32 {
+    let clockwise = raw
+        .side_data_list
+        .iter()
+        .find_map(|side_data| side_data.rotation)
+        .map(|rotation| -rotation)
+        .or_else(|| raw.tags.get("rotate").and_then(|value| parse_number(value)));
+
+    clockwise
+        .map(|degrees| (degrees.round() as i64).rem_euclid(360) as u32)
+        .unwrap_or_default()
+}
+
+fn known_value(value: Option<String>) -> Option<String> {
+    value.filter(|text| !text.is_empty() && text != "unknown" && text != "und")
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Option<T> {
+    value.trim().parse::<T>().ok()
+}
+
+fn parse_kbps(value: &str) -> Option<u32> {
+    parse_number::<f64>(value)
+        .filter(|bit_rate| *bit_rate > 0.0)
+        .map(|bit_rate| (bit_rate / 1000.0).floor() as u32)
+}
+
+fn parse_rational(value: &str) -> Option<f64> {
+    let (numerator, denominator) = value.split_once('/')?;
+    let numerator = parse_number::<f64>(numerator)?;
+    let denominator = parse_number::<f64>(denominator)?;
+    if numerator <= 0.0 || denominator <= 0.0 {
+        return None;
+    }
+
+    Some(numerator / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_media_info, StreamKind};
+
+    const HDR_MOVIE_PROBE: &str = r#"{
+        "streams": [
+            {
+                "index": 0,
+                "codec_name": "hevc",
+                "profile": "Main 10",
+                "codec_type": "video",
+                "width": 3840,
+                "height": 2160,
+                "pix_fmt": "yuv420p10le",
+                "color_range": "tv",
+                "color_space": "bt2020nc",
+                "color_transfer": "smpte2084",
+                "color_primaries": "bt2020",
+                "r_frame_rate": "24000/1001",
+                "avg_frame_rate": "24000/1001",
+                "bit_rate": "25000000",
+                "nb_frames": "2876",
+                "disposition": { "default": 1, "forced": 0, "attached_pic": 0 },
+                "tags": { "language": "und" },
+                "side_data_list": [
+                    { "side_data_type": "Display Matrix", "rotation": -90 }
+                ]
+            },
+            {
+                "index": 1,
+                "codec_name": "eac3",
+                "codec_type": "audio",
+                "sample_rate": "48000",
+                "channels": 6,
+                "channel_layout": "5.1(side)",
+                "bit_rate": "640000",
+                "disposition": { "default": 1, "original": 1 },
+                "tags": { "language": "eng", "title": "Surround" }
+            },
+            {
+                "index": 2,
+                "codec_name": "aac",
+                "codec_type": "audio",
+                "sample_rate": "44100",
+                "channels": 2,
+                "disposition": { "default": 0, "dub": 1 },
+                "tags": { "language": "fra" }
+            },
+            {
+                "index": 3,
+                "codec_name": "subrip",
+                "codec_type": "subtitle",
+                "disposition": { "forced": 1, "hearing_impaired": 0 },
+                "tags": { "language": "eng" }
+            },
+            {
+                "index": 4,
+                "codec_name": "mjpeg",
+                "codec_type": "video",
+                "width": 600,
+                "height": 600,
+                "avg_frame_rate": "0/0",
+                "r_frame_rate": "90000/1",
+                "disposition": { "attached_pic": 1 }
+            }
+        ],
+        "chapters": [
+            { "id": 0, "start_time": "0.000000", "end_time": "60.000000", "tags": { "title": "Opening" } },
+            { "id": 1, "start_time": "60.000000", "end_time": "119.953000", "tags": {} }
+        ],
+        "format": {
+            "format_name": "matroska,webm",
+            "duration": "119.953000",
+            "size": "380000000",
+            "bit_rate": "25343300"
+        }
+    }"#;
+
+    #[test]
+    fn parse_media_info_reads_format_and_chapters() {
+        let media = parse_media_info(HDR_MOVIE_PROBE).expect("fixture should parse");
+
+        assert_eq!(media.format_name, "matroska,webm");
+        assert!((media.duration_sec - 119.953).abs() < 1e-9);
+        assert_eq!(media.size_bytes, Some(380_000_000));
+        assert_eq!(media.bit_rate_kbps, Some(25_343));
+        assert_eq!(media.chapters.len(), 2);
+        assert_eq!(media.chapters[0].title.as_deref(), Some("Opening"));
+        assert_eq!(media.chapters[1].title, None);
+        assert!((media.chapters[1].end_sec - 119.953).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_media_info_reads_video_details() {
+        let media = parse_media_info(HDR_MOVIE_PROBE).expect("fixture should parse");
+        let video = media.primary_video().expect("primary video should exist");
+        let details = video.video.as_ref().expect("video details should exist");
+
+        assert_eq!(video.index, 0);
+        assert_eq!(video.codec_name, "hevc");
+        assert_eq!(video.profile.as_deref(), Some("Main 10"));
+        assert_eq!(video.frame_count, Some(2876));
+        assert_eq!(video.language, None);
+        assert!(video.disposition.default);
+        assert_eq!((details.width, details.height), (3840, 2160));
+        assert!((details.frame_rate.unwrap_or_default() - 23.976).abs() < 0.001);
+        assert_eq!(details.pixel_format.as_deref(), Some("yuv420p10le"));
+        assert_eq!(details.color.primaries.as_deref(), Some("bt2020"));
+        assert!(details.hdr);
+        assert_eq!(details.rotation_deg, 90);
+    }
+
+    #[test]
+    fn parse_media_info_reads_audio_and_subtitle_tracks() {
+        let media = parse_media_info(HDR_MOVIE_PROBE).expect("fixture should parse");
+        let audio: Vec<_> = media.streams_of(StreamKind::Audio).collect();
+        let subtitles: Vec<_> = media.streams_of(StreamKind::Subtitle).collect();
+
+        assert_eq!(audio.len(), 2);
+        assert_eq!(audio[0].language.as_deref(), Some("eng"));
+        assert_eq!(audio[0].title.as_deref(), Some("Surround"));
+        assert_eq!(audio[0].bit_rate_kbps, Some(640));
+        assert!(audio[0].disposition.original);
+        let surround = audio[0].audio.as_ref().expect("audio details should exist");
+        assert_eq!(surround.channels, Some(6));
+        assert_eq!(surround.sample_rate, Some(48_000));
+        assert!(audio[1].disposition.dub);
+        assert_eq!(audio[1].bit_rate_kbps, None);
+
+        assert_eq!(subtitles.len(), 1);
+        assert!(subtitles[0].disposition.forced);
+        assert!(subtitles[0].video.is_none());
+    }
+
+    #[test]
+    fn parse_media_info_skips_cover_art_as_primary_video() {
+        let media = parse_media_info(HDR_MOVIE_PROBE).expect("fixture should parse");
+        let cover = &media.streams[4];
+
+        assert!(cover.disposition.attached_pic);
+        assert_eq!(
+            cover.video.as_ref().and_then(|video| video.frame_rate),
+            Some(90_000.0)
+        );
+        assert_eq!(media.primary_video().map(|stream| stream.index), Some(0));
+    }
+
+    #[test]
+    fn parse_media_info_rejects_missing_duration() {
+        let error = parse_media_info(r#"{"streams": [], "format": {"duration": "N/A"}}"#)
+            .expect_err("missing duration should fail");
+        assert!(error.contains("duration"));
+    }
+}
@@ -1,13 +1,28 @@
 mod command;
+mod encoder_backends;
+mod encoder_help;
 mod encoders;
+mod media_info;
 mod output;
 mod path_resolution;
 mod probe;
 mod rate_control;
+mod self_test;
+mod vmaf;
 
 pub use command::{hidden_command, hidden_program_command};
+pub use encoder_backends::{
+    encoder_backend, MAX_PRESET_LEVEL, MAX_QUALITY_LEVEL, MIN_PRESET_LEVEL,
+};
+pub use encoder_help::{describe_encoder, EncoderDescription};
 pub use encoders::{list_encoders, resolve_encoder_candidates};
-pub use output::default_output_for_input;
+pub use media_info::{MediaInfo, StreamInfo, StreamKind};
+pub use output::{output_marker_args, temp_output_for};
 pub use path_resolution::resolve_tool_path;
-pub use probe::read_probe_metadata;
-pub use rate_control::video_rate_args;
+pub use probe::{check_video_file, probe_media_info};
+pub use rate_control::{
+    supports_constant_quality, supports_two_pass, video_preset_args, video_quality_args,
+    video_rate_args,
+};
+pub use self_test::test_encoder;
+pub use vmaf::{parse_vmaf_log, vmaf_filter_graph};
@@ -1,28 +1,38 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub fn default_output_for_input(input_path: &str) -> Result<String, String> {
-    let path = Path::new(input_path);
-    let parent = path
-        .parent()
-        .ok_or_else(|| "Input path has no parent folder.".to_string())?;
-    let stem = path
+const PARTIAL_OUTPUT_SUFFIX: &str = ".partial";
+pub const OUTPUT_MARKER_TAG: &str = "description";
+pub const OUTPUT_MARKER_VALUE: &str = "Converted by vid2av1";
+
+pub fn output_marker_args() -> Vec<String> {
+    vec![
+        "-metadata".to_string(),
+        format!("{OUTPUT_MARKER_TAG}={OUTPUT_MARKER_VALUE}"),
+    ]
+}
+
+pub fn temp_output_for(output_path: &Path) -> Result<PathBuf, String> {
+    let stem = output_path
         .file_stem()
-        .ok_or_else(|| "Input path has no file name.".to_string())?
+        .ok_or_else(|| "Output path has no file name.".to_string())?
         .to_string_lossy();
-    Ok(parent
-        .join(format!("{stem}.av1.mp4"))
-        .to_string_lossy()
-        .to_string())
+    let extension = output_path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    Ok(output_path.with_file_name(format!("{stem}{PARTIAL_OUTPUT_SUFFIX}{extension}")))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::default_output_for_input;
+    use std::path::Path;
+
+    use super::temp_output_for;
 
     #[test]
-    fn default_output_for_input_replaces_extension() {
-        let output =
-            default_output_for_input("videos/input.mkv").expect("output path should be generated");
-        assert!(output.ends_with("input.av1.mp4"));
+    fn temp_output_for_stays_next_to_output() {
+        let temp_output = temp_output_for(Path::new("videos/input.av1.mp4"))
+            .expect("temp output path should be generated");
+        assert_eq!(temp_output, Path::new("videos/input.av1.partial.mp4"));
     }
 }
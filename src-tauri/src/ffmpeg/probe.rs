@@ -1,27 +1,55 @@
+use std::collections::HashMap;
 use std::path::Path;
 
+use serde::Deserialize;
+
 use super::command::hidden_command;
+use super::media_info::{parse_media_info, MediaInfo};
+use super::output::{OUTPUT_MARKER_TAG, OUTPUT_MARKER_VALUE};
+
+pub struct VideoCheck {
+    pub has_video: bool,
+    pub duration_sec: Option<f64>,
+    pub converted_output: bool,
+}
+
+#[derive(Deserialize)]
+struct RawVideoCheck {
+    #[serde(default)]
+    streams: Vec<RawCheckStream>,
+    format: Option<RawCheckFormat>,
+}
+
+#[derive(Deserialize)]
+struct RawCheckStream {
+    codec_type: Option<String>,
+    #[serde(default)]
+    disposition: RawCheckDisposition,
+}
+
+#[derive(Deserialize, Default)]
+struct RawCheckDisposition {
+    #[serde(default)]
+    attached_pic: u8,
+}
 
-pub struct ProbeMetadata {
-    pub duration_sec: f64,
-    pub audio_bitrate_kbps: u32,
+#[derive(Deserialize)]
+struct RawCheckFormat {
+    duration: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
 }
 
-pub fn read_probe_metadata(
-    ffprobe_path: &Path,
-    input_path: &str,
-    fallback_audio_kbps: u32,
-) -> Result<ProbeMetadata, String> {
+pub fn probe_media_info(ffprobe_path: &Path, input_path: &str) -> Result<MediaInfo, String> {
     let output = hidden_command(ffprobe_path)
         .args([
             "-v",
             "error",
-            "-select_streams",
-            "a:0",
-            "-show_entries",
-            "format=duration:stream=bit_rate",
             "-of",
-            "default=noprint_wrappers=1",
+            "json",
+            "-show_format",
+            "-show_streams",
+            "-show_chapters",
             input_path,
         ])
         .output()
@@ -34,35 +62,81 @@ pub fn read_probe_metadata(
         ));
     }
 
-    let text = String::from_utf8_lossy(&output.stdout);
-    let duration = find_value(&text, "duration")
-        .and_then(|value| value.parse::<f64>().ok())
-        .ok_or_else(|| "Expected ffprobe duration output.".to_string())?;
-    if duration <= 0.0 {
-        return Err("Duration must be > 0.".to_string());
+    parse_media_info(&String::from_utf8_lossy(&output.stdout))
+}
+
+pub fn check_video_file(ffprobe_path: &Path, input_path: &str) -> Result<VideoCheck, String> {
+    let output = hidden_command(ffprobe_path)
+        .args([
+            "-v",
+            "error",
+            "-of",
+            "json",
+            "-show_entries",
+            &format!(
+                "format=duration:format_tags={OUTPUT_MARKER_TAG}:stream=codec_type:stream_disposition=attached_pic"
+            ),
+            input_path,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
 
-    let audio_bitrate_kbps = find_value(&text, "bit_rate")
-        .and_then(|value| value.parse::<f64>().ok())
-        .filter(|bit_rate| *bit_rate > 0.0)
-        .map(|bit_rate| (bit_rate / 1000.0).floor() as u32)
-        .unwrap_or(fallback_audio_kbps);
+    parse_video_check(&String::from_utf8_lossy(&output.stdout))
+}
 
-    Ok(ProbeMetadata {
-        duration_sec: duration,
-        audio_bitrate_kbps,
+fn parse_video_check(json: &str) -> Result<VideoCheck, String> {
+    let raw: RawVideoCheck =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse ffprobe output: {e}"))?;
+    Ok(VideoCheck {
+        has_video: raw.streams.iter().any(|stream| {
+            stream.codec_type.as_deref() == Some("video") && stream.disposition.attached_pic == 0
+        }),
+        duration_sec: raw
+            .format
+            .as_ref()
+            .and_then(|format| format.duration.as_deref())
+            .and_then(|duration| duration.parse::<f64>().ok()),
+        converted_output: raw.format.is_some_and(|format| {
+            format.tags.iter().any(|(key, value)| {
+                key.eq_ignore_ascii_case(OUTPUT_MARKER_TAG) && value == OUTPUT_MARKER_VALUE
+            })
+        }),
     })
 }
 
-fn find_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
-    for line in text.lines() {
-        let trimmed = line.trim();
-        if let Some((found_key, value)) = trimmed.split_once('=') {
-            if found_key == key {
-                return Some(value.trim());
-            }
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::parse_video_check;
+
+    #[test]
+    fn parse_video_check_ignores_cover_art() {
+        let audio_with_cover = r#"{
+            "streams": [
+                {"codec_type": "audio", "disposition": {"attached_pic": 0}},
+                {"codec_type": "video", "disposition": {"attached_pic": 1}}
+            ],
+            "format": {"duration": "215.300000"}
+        }"#;
+        let movie = r#"{
+            "streams": [{"codec_type": "video", "disposition": {"attached_pic": 0}}],
+            "format": {"tags": {"DESCRIPTION": "Converted by vid2av1"}}
+        }"#;
 
-    None
+        let audio_check = parse_video_check(audio_with_cover).expect("fixture should parse");
+        let movie_check = parse_video_check(movie).expect("fixture should parse");
+
+        assert!(!audio_check.has_video);
+        assert!(!audio_check.converted_output);
+        assert_eq!(audio_check.duration_sec, Some(215.3));
+        assert!(movie_check.has_video);
+        assert_eq!(movie_check.duration_sec, None);
+        assert!(movie_check.converted_output);
+    }
 }
@@ -1,20 +1,126 @@
-pub fn video_rate_args(video_bitrate_kbps: u32, strict_size: bool, encoder: &str) -> Vec<String> {
-    let mut args = vec!["-b:v".to_string(), format!("{video_bitrate_kbps}k")];
+use super::encoder_backends::encoder_backend;
+use super::encoder_help::{EncoderDescription, RateControlMode};
 
-    if strict_size {
-        if encoder == "av1_nvenc" {
-            args.push("-rc".to_string());
-            args.push("cbr".to_string());
-        }
+pub fn supports_two_pass(encoder: &str) -> bool {
+    encoder_backend(encoder).supports_two_pass()
+}
+
+pub fn supports_constant_quality(encoder: &str, description: Option<&EncoderDescription>) -> bool {
+    encoder_backend(encoder).supports_constant_quality()
+        && description.is_none_or(|description| {
+            description.supports_rate_control(RateControlMode::ConstantQuality)
+        })
+}
+
+pub fn video_quality_args(
+    quality_level: u32,
+    encoder: &str,
+    description: Option<&EncoderDescription>,
+) -> Vec<String> {
+    encoder_backend(encoder).cq_args(quality_level, description)
+}
+
+pub fn video_preset_args(
+    preset_level: u32,
+    encoder: &str,
+    description: Option<&EncoderDescription>,
+) -> Vec<String> {
+    encoder_backend(encoder).preset_args(preset_level, description)
+}
+
+pub fn video_rate_args(
+    video_bitrate_kbps: u32,
+    strict_size: bool,
+    encoder: &str,
+    description: Option<&EncoderDescription>,
+) -> Vec<String> {
+    let backend = encoder_backend(encoder);
+    let cbr_supported = description.is_none_or(|description| {
+        description.supports_rate_control(RateControlMode::ConstantBitrate)
+    });
+    if strict_size && cbr_supported {
+        backend.cbr_args(video_bitrate_kbps, description)
+    } else {
+        backend.vbr_args(video_bitrate_kbps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{supports_constant_quality, video_quality_args, video_rate_args};
+    use crate::ffmpeg::encoder_help::parse_encoder_help;
+
+    #[test]
+    fn video_quality_args_maps_level_to_native_knobs() {
+        assert_eq!(video_quality_args(30, "libsvtav1", None), ["-crf", "30"]);
+        assert_eq!(
+            video_quality_args(30, "libaom-av1", None),
+            ["-crf", "30", "-b:v", "0"]
+        );
+        assert_eq!(
+            video_quality_args(63, "av1_nvenc", None),
+            ["-rc", "vbr", "-cq", "51", "-b:v", "0"]
+        );
+        assert_eq!(video_quality_args(63, "librav1e", None), ["-qp", "255"]);
+        assert_eq!(
+            video_quality_args(0, "av1_qsv", None),
+            ["-global_quality", "1"]
+        );
+    }
+
+    #[test]
+    fn video_rate_args_uses_the_introspected_cbr_selector() {
+        let vaapi = parse_encoder_help(include_str!("fixtures/help_av1_vaapi.txt"))
+            .expect("vaapi help should parse");
+        let svt = parse_encoder_help(include_str!("fixtures/help_libsvtav1.txt"))
+            .expect("svt help should parse");
 
-        let buf = (video_bitrate_kbps * 2).max(1000);
-        args.push("-minrate".to_string());
-        args.push(format!("{video_bitrate_kbps}k"));
-        args.push("-maxrate".to_string());
-        args.push(format!("{video_bitrate_kbps}k"));
-        args.push("-bufsize".to_string());
-        args.push(format!("{buf}k"));
+        assert_eq!(
+            video_rate_args(800, true, "av1_vaapi", Some(&vaapi)),
+            [
+                "-b:v", "800k", "-rc_mode", "CBR", "-minrate", "800k", "-maxrate", "800k",
+                "-bufsize", "1600k"
+            ]
+        );
+        assert_eq!(
+            video_rate_args(800, true, "libsvtav1", Some(&svt)),
+            ["-b:v", "800k", "-minrate", "800k", "-maxrate", "800k", "-bufsize", "1600k"]
+        );
+        assert_eq!(
+            video_rate_args(800, false, "av1_vaapi", Some(&vaapi)),
+            ["-b:v", "800k"]
+        );
     }
 
-    args
+    #[test]
+    fn video_rate_args_without_a_description_only_switches_nvenc_to_cbr() {
+        assert_eq!(
+            video_rate_args(800, true, "av1_nvenc", None),
+            [
+                "-b:v", "800k", "-rc", "cbr", "-minrate", "800k", "-maxrate", "800k", "-bufsize",
+                "1600k"
+            ]
+        );
+        for encoder in ["av1_amf", "av1_mf", "av1_vaapi"] {
+            assert_eq!(
+                video_rate_args(800, true, encoder, None),
+                ["-b:v", "800k", "-minrate", "800k", "-maxrate", "800k", "-bufsize", "1600k"],
+                "{encoder}"
+            );
+        }
+    }
+
+    #[test]
+    fn rate_args_fall_back_when_the_encoder_lacks_a_mode() {
+        let mut nvenc = parse_encoder_help(include_str!("fixtures/help_av1_nvenc.txt"))
+            .expect("nvenc help should parse");
+        nvenc.rate_control_modes.clear();
+
+        assert_eq!(
+            video_rate_args(800, true, "av1_nvenc", Some(&nvenc)),
+            ["-b:v", "800k"]
+        );
+        assert!(!supports_constant_quality("av1_nvenc", Some(&nvenc)));
+        assert!(supports_constant_quality("av1_nvenc", None));
+    }
 }
@@ -0,0 +1,106 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::command::hidden_command;
+
+const TEST_SOURCE: &str = "color=c=black:s=320x240:r=25";
+const TEST_FRAMES: &str = "5";
+const TEST_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_REASON_LINES: usize = 3;
+
+pub fn test_encoder(ffmpeg_path: &Path, encoder: &str) -> Result<(), String> {
+    let mut child = hidden_command(ffmpeg_path)
+        .args([
+            "-hide_banner",
+            "-nostdin",
+            "-v",
+            "error",
+            "-f",
+            "lavfi",
+            "-i",
+            TEST_SOURCE,
+            "-frames:v",
+            TEST_FRAMES,
+            "-pix_fmt",
+            "yuv420p",
+            "-c:v",
+            encoder,
+            "-f",
+            "null",
+            "-",
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {e}"))?;
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() >= TEST_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "Test encode did not finish within {} seconds.",
+                    TEST_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for ffmpeg: {e}")),
+        }
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to read ffmpeg output: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(failure_reason(
+        &String::from_utf8_lossy(&output.stderr),
+        &output.status.to_string(),
+    ))
+}
+
+fn failure_reason(stderr: &str, status: &str) -> String {
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return format!("Test encode failed ({status}).");
+    }
+    lines
+        .into_iter()
+        .take(MAX_REASON_LINES)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::failure_reason;
+
+    #[test]
+    fn failure_reason_keeps_first_stderr_lines() {
+        let stderr = "\n[av1_nvenc @ 0x1] Cannot load nvcuda.dll\n\
+            [vost#0:0/av1_nvenc @ 0x2] Error while opening encoder\n\
+            Error sending frames to consumers: Operation not permitted\n\
+            Conversion failed!\n\n";
+        assert_eq!(
+            failure_reason(stderr, "exit code: 1"),
+            "[av1_nvenc @ 0x1] Cannot load nvcuda.dll \
+             [vost#0:0/av1_nvenc @ 0x2] Error while opening encoder \
+             Error sending frames to consumers: Operation not permitted"
+        );
+        assert_eq!(
+            failure_reason("  \n", "exit code: 1"),
+            "Test encode failed (exit code: 1)."
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct VmafLog {
+    pooled_metrics: VmafPooledMetrics,
+}
+
+#[derive(Deserialize)]
+struct VmafPooledMetrics {
+    vmaf: VmafPooledScore,
+}
+
+#[derive(Deserialize)]
+struct VmafPooledScore {
+    mean: f64,
+}
+
+pub fn vmaf_filter_graph(reference_stream_index: u32, log_path: &Path) -> String {
+    format!(
+        "[0:v]setpts=PTS-STARTPTS[distorted];\
+         [1:{reference_stream_index}]setpts=PTS-STARTPTS[reference];\
+         [distorted][reference]libvmaf=log_fmt=json:log_path={}",
+        escape_filter_value(&log_path.to_string_lossy())
+    )
+}
+
+pub fn parse_vmaf_log(text: &str) -> Result<f64, String> {
+    let log: VmafLog =
+        serde_json::from_str(text).map_err(|e| format!("Failed to parse VMAF log: {e}"))?;
+    let mean = log.pooled_metrics.vmaf.mean;
+    if !mean.is_finite() {
+        return Err("VMAF log contains no valid score.".to_string());
+    }
+
+    Ok(mean)
+}
+
+fn escape_filter_value(value: &str) -> String {
+    let option_value = escape_chars(&value.replace('\\', "/"), &['\\', '\'', ':']);
+    escape_chars(&option_value, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+fn escape_chars(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if special.contains(&ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{parse_vmaf_log, vmaf_filter_graph};
+
+    #[test]
+    fn vmaf_filter_graph_escapes_windows_log_path() {
+        let graph = vmaf_filter_graph(0, Path::new(r"C:\Temp\vid2av1\score.json"));
+        assert!(graph.contains("[1:0]setpts"));
+        assert!(graph.ends_with(r"log_path=C\\:/Temp/vid2av1/score.json"));
+
+        let graph = vmaf_filter_graph(0, Path::new("/tmp/it's [1],a;b.json"));
+        assert!(graph.ends_with(r"log_path=/tmp/it\\\'s \[1\]\,a\;b.json"));
+    }
+
+    #[test]
+    fn parse_vmaf_log_reads_pooled_mean() {
+        let log = r#"{
+            "version": "2.3.1",
+            "frames": [],
+            "pooled_metrics": {
+                "vmaf": { "min": 88.1, "max": 99.2, "mean": 94.37, "harmonic_mean": 94.2 }
+            }
+        }"#;
+        let score = parse_vmaf_log(log).expect("log should parse");
+        assert!((score - 94.37).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn parse_vmaf_log_rejects_missing_metrics() {
+        assert!(parse_vmaf_log(r#"{"frames": []}"#).is_err());
+    }
+}
@@ -7,11 +7,20 @@ mod error_protocol;
 mod ffmpeg;
 mod model;
 mod state;
+#[cfg(test)]
+mod test_fs;
 
-use commands::{cancel_conversion, convert_video, pick_auto_av1_encoder, show_main_window};
+use commands::{
+    cancel_conversion, cancel_job, convert_video, discard_interrupted_jobs, enqueue_jobs,
+    list_jobs, pause_conversion, pick_auto_av1_encoder, preview_conversion, probe_media,
+    refresh_encoders, remove_job, reorder_jobs, resume_conversion, resume_interrupted_jobs,
+    scan_folders, set_concurrency_limit, show_main_window, skip_job, stop_after_current,
+    take_queue_reset_notice,
+};
+use convert::start_job_worker;
 use state::{
-    terminate_all_active_ffmpeg, ActiveConversionControl, ActiveFfmpegPids, Av1EncoderCache,
-    GlobalCancelFlag,
+    close_job_queue_store, restore_job_queue, terminate_all_active_ffmpeg, ActiveConversionControl,
+    ActiveFfmpegPids, Av1EncoderCache, GlobalCancelFlag, JobQueue, QueueResetNotice,
 };
 use tauri::Manager;
 
@@ -21,18 +30,41 @@ fn main() {
         .manage(ActiveConversionControl::default())
         .manage(Av1EncoderCache::default())
         .manage(GlobalCancelFlag::default())
+        .manage(JobQueue::default())
+        .manage(QueueResetNotice::default())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            restore_job_queue(app.handle());
+            start_job_worker(app.handle().clone());
+            Ok(())
+        })
         .on_window_event(|window, event| {
             if matches!(event, tauri::WindowEvent::CloseRequested { .. }) {
-                if let Err(error) = terminate_all_active_ffmpeg(window.app_handle()) {
-                    eprintln!("Failed to terminate active ffmpeg processes: {error}");
-                }
+                let _ = close_job_queue_store(window.app_handle());
+                let _ = terminate_all_active_ffmpeg(window.app_handle());
             }
         })
         .invoke_handler(tauri::generate_handler![
             pick_auto_av1_encoder,
+            refresh_encoders,
             convert_video,
+            probe_media,
+            preview_conversion,
+            scan_folders,
+            enqueue_jobs,
+            list_jobs,
+            remove_job,
+            reorder_jobs,
+            set_concurrency_limit,
+            resume_interrupted_jobs,
+            discard_interrupted_jobs,
+            take_queue_reset_notice,
             cancel_conversion,
+            cancel_job,
+            skip_job,
+            stop_after_current,
+            pause_conversion,
+            resume_conversion,
             show_main_window
         ])
         .run(tauri::generate_context!())
@@ -1,28 +1,296 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+use crate::ffmpeg::EncoderDescription;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TargetPolicy {
+    Ratio { percent: f64 },
+    SizeMb { megabytes: f64 },
+    MaxBitrate { kbps: u32 },
+}
+
+impl Default for TargetPolicy {
+    fn default() -> Self {
+        Self::Ratio { percent: 50.0 }
+    }
+}
+
+impl fmt::Display for TargetPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ratio { percent } => write!(f, "{percent}% of source size"),
+            Self::SizeMb { megabytes } => write!(f, "{megabytes} MB output size"),
+            Self::MaxBitrate { kbps } => write!(f, "{kbps} kbps maximum bitrate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollisionPolicy {
+    Skip,
+    Overwrite,
+    #[default]
+    AutoNumber,
+}
+
+pub type JobId = u64;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConvertRequest {
     pub input_path: String,
     #[serde(default)]
     pub av1_encoder: Option<String>,
+    #[serde(default)]
+    pub target_policy: TargetPolicy,
+    #[serde(default)]
+    pub quality_level: Option<u32>,
+    #[serde(default)]
+    pub preset_level: Option<u32>,
+    #[serde(default)]
+    pub vmaf_target: Option<f64>,
+    #[serde(default)]
+    pub two_pass: bool,
+    #[serde(default)]
+    pub max_encode_attempts: Option<u32>,
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub output_template: Option<String>,
+    #[serde(default)]
+    pub source_root: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodeAttempt {
+    pub attempt: u32,
+    pub video_bitrate_kbps: u32,
+    pub output_size_bytes: u64,
+    pub within_target: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VmafSearchStep {
+    pub quality_level: u32,
+    pub sample_scores: Vec<f64>,
+    pub mean_score: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VmafSearchResult {
+    pub target_score: f64,
+    pub quality_level: u32,
+    pub target_met: bool,
+    pub sample_starts_sec: Vec<f64>,
+    pub steps: Vec<VmafSearchStep>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConvertSummary {
     pub output_path: String,
-    pub target_size_bytes: u64,
+    pub target_policy: Option<TargetPolicy>,
+    pub target_size_bytes: Option<u64>,
+    pub quality_level: Option<u32>,
+    pub output_size_bytes: u64,
     pub audio_bitrate_kbps: u32,
-    pub video_bitrate_kbps: u32,
+    pub audio_track_count: usize,
+    pub subtitle_track_count: usize,
+    pub video_bitrate_kbps: Option<u32>,
     pub av1_encoder: String,
+    pub two_pass: bool,
+    pub attempts: Vec<EncodeAttempt>,
+    pub vmaf_search: Option<VmafSearchResult>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaProbeSummary {
+    pub input_path: String,
+    pub container: String,
+    pub duration_sec: f64,
+    pub size_bytes: Option<u64>,
+    pub bit_rate_kbps: Option<u32>,
+    pub video: VideoTrackSummary,
+    pub audio_tracks: Vec<AudioTrackSummary>,
+    pub subtitle_tracks: Vec<SubtitleTrackSummary>,
+    pub chapter_count: usize,
+    pub planned_output: Option<PlannedOutput>,
+    pub plan_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoTrackSummary {
+    pub index: u32,
+    pub codec: String,
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: Option<f64>,
+    pub bit_rate_kbps: Option<u32>,
+    pub hdr: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTrackSummary {
+    pub index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub channels: Option<u32>,
+    pub bit_rate_kbps: Option<u32>,
+    pub default: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleTrackSummary {
+    pub index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub forced: bool,
+    pub kept: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderCheck {
+    pub encoder: String,
+    pub usable: bool,
+    pub error: Option<String>,
+    pub description: Option<EncoderDescription>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedOutput {
+    pub output_path: String,
+    pub target_policy: Option<TargetPolicy>,
+    pub target_size_bytes: Option<u64>,
+    pub quality_level: Option<u32>,
+    pub video_bitrate_kbps: Option<u32>,
+    pub audio_bitrate_kbps: u32,
+    pub encoder_candidates: Vec<String>,
+    pub two_pass: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionPreview {
+    pub input_path: String,
+    pub output_path: String,
+    pub ffmpeg_path: String,
+    pub ffprobe_path: String,
+    pub encoder_candidates: Vec<String>,
+    pub encoders: Vec<EncoderPreview>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderPreview {
+    pub encoder: String,
+    pub two_pass: bool,
+    pub invocations: Vec<FfmpegInvocation>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegInvocation {
+    pub label: String,
+    pub args: Vec<String>,
+    pub runtime_values: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ProgressEvent {
+    pub job_id: Option<JobId>,
     pub percent: f64,
     pub speed: Option<f64>,
     pub eta_seconds: Option<f64>,
     pub label: String,
+    pub paused: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderScanRequest {
+    pub folders: Vec<String>,
+    #[serde(default = "default_true")]
+    pub recursive: bool,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub include_globs: Vec<String>,
+    #[serde(default)]
+    pub exclude_globs: Vec<String>,
+    #[serde(default)]
+    pub min_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub min_duration_sec: Option<f64>,
+    #[serde(default = "default_true")]
+    pub skip_converted: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderScanResult {
+    pub files: Vec<ScannedFile>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedFile {
+    pub input_path: String,
+    pub source_root: String,
+    pub size_bytes: u64,
+    pub duration_sec: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Canceled,
+    Skipped,
+    Stopped,
+    Interrupted,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionJob {
+    pub id: JobId,
+    pub request: ConvertRequest,
+    pub status: JobStatus,
+    pub result: Option<ConvertSummary>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub partial_output_path: Option<String>,
 }
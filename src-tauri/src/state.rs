@@ -1,14 +1,41 @@
 mod cancellation;
 mod encoder_cache;
+mod encoder_store;
+mod job_queue;
+mod json_file;
+mod pause;
 mod process_registry;
+mod process_suspension;
+mod queue_recovery;
+mod queue_store;
+mod stop_reason;
 mod termination;
 mod types;
 
-pub use cancellation::{cancel_active_conversion, clear_cancel_requested, is_cancel_requested};
-pub use encoder_cache::{get_cached_av1_encoders, set_cached_av1_encoders};
+pub use cancellation::{cancel_active_conversion, stop_job, stop_requested};
+pub use encoder_cache::{
+    clear_cached_av1_encoders, get_cached_av1_encoders, set_cached_av1_encoders,
+};
+pub use encoder_store::{
+    encoder_cache_file_path, load_encoder_checks, remove_encoder_checks, save_encoder_checks,
+    EncoderCacheKey,
+};
+pub use job_queue::{
+    close_job_queue_store, enqueue_jobs, finish_job, list_jobs, remove_job, reorder_jobs,
+    reserve_partial_output, set_concurrency_limit, settle_queued_jobs, wait_for_job,
+    wait_for_next_job, with_partial_output_claims,
+};
+pub use pause::{pause_conversion, resume_conversion};
 pub use process_registry::{
-    register_active_conversion, register_ffmpeg_pid, unregister_active_conversion,
-    unregister_ffmpeg_pid,
+    get_active_conversion, register_active_conversion, register_ffmpeg_pid,
+    unregister_active_conversion, unregister_ffmpeg_pid,
 };
+pub use queue_recovery::{
+    discard_interrupted_jobs, restore_job_queue, resume_interrupted_jobs, take_queue_reset_notice,
+};
+pub use stop_reason::StopReason;
 pub use termination::terminate_all_active_ffmpeg;
-pub use types::{ActiveConversionControl, ActiveFfmpegPids, Av1EncoderCache, GlobalCancelFlag};
+pub use types::{
+    ActiveConversionControl, ActiveFfmpegPids, Av1EncoderCache, ConversionControl,
+    GlobalCancelFlag, JobQueue, QueueResetNotice,
+};
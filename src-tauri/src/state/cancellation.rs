@@ -3,50 +3,55 @@ use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Manager};
 
 use crate::error_protocol::{is_error_code, ERROR_CODE_NO_ACTIVE_CONVERSION};
+use crate::model::JobId;
 
-use super::process_registry::set_active_conversion_cancel_requested;
-use super::process_registry::{get_active_conversion, is_active_conversion_cancel_requested};
-use super::termination::terminate_all_active_ffmpeg;
+use super::job_queue::settle_queued_job;
+use super::process_registry::{collect_active_conversions, get_active_conversion};
+use super::stop_reason::StopReason;
+use super::termination::{terminate_pid, terminate_registered_ffmpeg};
 use super::types::GlobalCancelFlag;
 
 pub fn cancel_active_conversion(app: &AppHandle) -> Result<(), String> {
-    request_cancel(app);
-
-    match get_active_conversion(app) {
-        Ok(control) => {
-            control.mark_cancel_requested();
-            let _ = control.send_quit_command();
-        }
-        Err(error) => {
-            if !is_error_code(&error, ERROR_CODE_NO_ACTIVE_CONVERSION) {
-                return Err(error);
-            }
-        }
+    for control in collect_active_conversions(app)? {
+        control.request_stop(StopReason::Canceled);
+        let _ = control.resume();
+        let _ = control.send_quit_command();
     }
 
-    terminate_all_active_ffmpeg(app)?;
-    Ok(())
+    terminate_registered_ffmpeg(app)
 }
 
-pub fn is_cancel_requested(app: &AppHandle) -> bool {
-    if app.state::<GlobalCancelFlag>().0.load(Ordering::Relaxed) {
-        return true;
+pub fn stop_job(app: &AppHandle, job_id: JobId, reason: StopReason) -> Result<(), String> {
+    if settle_queued_job(app, job_id, reason)? {
+        return Ok(());
     }
 
-    match is_active_conversion_cancel_requested(app) {
-        Ok(requested) => requested,
-        Err(error) => {
-            eprintln!("Failed to read active conversion cancel state: {error}");
-            true
-        }
+    let control = get_active_conversion(app, job_id)?;
+    control.request_stop(reason);
+    let _ = control.resume();
+    let _ = control.send_quit_command();
+    if let Some(pid) = control.process_pid()? {
+        terminate_pid(pid);
     }
+    Ok(())
 }
 
-pub fn clear_cancel_requested(app: &AppHandle) -> Result<(), String> {
-    app.state::<GlobalCancelFlag>()
-        .0
-        .store(false, Ordering::Relaxed);
-    set_active_conversion_cancel_requested(app, false)
+pub fn stop_requested(
+    app: &AppHandle,
+    job_id: Option<JobId>,
+) -> Result<Option<StopReason>, String> {
+    if app.state::<GlobalCancelFlag>().0.load(Ordering::Relaxed) {
+        return Ok(Some(StopReason::Canceled));
+    }
+
+    let Some(job_id) = job_id else {
+        return Ok(None);
+    };
+    match get_active_conversion(app, job_id) {
+        Ok(control) => Ok(control.stop_reason()),
+        Err(error) if is_error_code(&error, ERROR_CODE_NO_ACTIVE_CONVERSION) => Ok(None),
+        Err(error) => Err(error),
+    }
 }
 
 pub fn request_cancel(app: &AppHandle) {
@@ -1,8 +1,10 @@
 use tauri::{AppHandle, Manager};
 
+use crate::model::EncoderCheck;
+
 use super::types::Av1EncoderCache;
 
-pub fn get_cached_av1_encoders(app: &AppHandle) -> Result<Option<Vec<String>>, String> {
+pub fn get_cached_av1_encoders(app: &AppHandle) -> Result<Option<Vec<EncoderCheck>>, String> {
     let state = app.state::<Av1EncoderCache>();
     let cache = state
         .0
@@ -11,12 +13,23 @@ pub fn get_cached_av1_encoders(app: &AppHandle) -> Result<Option<Vec<String>>, S
     Ok(cache.clone())
 }
 
-pub fn set_cached_av1_encoders(app: &AppHandle, encoders: Vec<String>) -> Result<(), String> {
+pub fn set_cached_av1_encoders(app: &AppHandle, encoders: Vec<EncoderCheck>) -> Result<(), String> {
+    replace_cached_av1_encoders(app, Some(encoders))
+}
+
+pub fn clear_cached_av1_encoders(app: &AppHandle) -> Result<(), String> {
+    replace_cached_av1_encoders(app, None)
+}
+
+fn replace_cached_av1_encoders(
+    app: &AppHandle,
+    encoders: Option<Vec<EncoderCheck>>,
+) -> Result<(), String> {
     let state = app.state::<Av1EncoderCache>();
     let mut cache = state
         .0
         .lock()
         .map_err(|_| "Failed to lock AV1 encoder cache.".to_string())?;
-    *cache = Some(encoders);
+    *cache = encoders;
     Ok(())
 }
@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::model::EncoderCheck;
+
+use super::json_file::{app_data_file_path, read_json_file, write_json_atomically};
+
+const ENCODER_CACHE_FILE_NAME: &str = "encoder-cache.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderCacheKey {
+    pub ffmpeg_path: String,
+    pub size_bytes: u64,
+    pub modified_unix_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncoderCacheFile {
+    key: EncoderCacheKey,
+    checks: Vec<EncoderCheck>,
+}
+
+pub fn encoder_cache_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app_data_file_path(app, ENCODER_CACHE_FILE_NAME)
+}
+
+pub fn load_encoder_checks(
+    path: &Path,
+    key: &EncoderCacheKey,
+) -> Result<Option<Vec<EncoderCheck>>, String> {
+    let file: Option<EncoderCacheFile> = read_json_file(path)?;
+    Ok(file.and_then(|file| (file.key == *key).then_some(file.checks)))
+}
+
+pub fn save_encoder_checks(
+    path: &Path,
+    key: &EncoderCacheKey,
+    checks: &[EncoderCheck],
+) -> Result<(), String> {
+    write_json_atomically(
+        path,
+        &EncoderCacheFile {
+            key: key.clone(),
+            checks: checks.to_vec(),
+        },
+    )
+}
+
+pub fn remove_encoder_checks(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {e}", path.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_encoder_checks, remove_encoder_checks, save_encoder_checks, EncoderCacheKey};
+    use crate::model::EncoderCheck;
+    use crate::test_fs::scratch_dir;
+
+    #[test]
+    fn encoder_checks_are_only_reused_for_the_same_ffmpeg_binary() {
+        let dir = scratch_dir("encoder-store");
+        let path = dir.join("encoder-cache.json");
+        let key = EncoderCacheKey {
+            ffmpeg_path: "C:\\vid2av1\\ffmpeg.exe".to_string(),
+            size_bytes: 1_000,
+            modified_unix_ms: 2_000,
+        };
+        let checks = vec![EncoderCheck {
+            encoder: "libsvtav1".to_string(),
+            usable: true,
+            error: None,
+            description: None,
+        }];
+
+        save_encoder_checks(&path, &key, &checks).expect("cache should save");
+        let same = load_encoder_checks(&path, &key).expect("cache should load");
+        let upgraded = EncoderCacheKey {
+            size_bytes: 1_024,
+            ..key.clone()
+        };
+        let other = load_encoder_checks(&path, &upgraded).expect("cache should load");
+        remove_encoder_checks(&path).expect("cache should be removed");
+        let removed = load_encoder_checks(&path, &key).expect("missing file is not an error");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            same.map(|checks| checks.len()),
+            Some(1),
+            "same binary should reuse the cache"
+        );
+        assert!(other.is_none(), "a replaced ffmpeg binary should miss");
+        assert!(removed.is_none());
+    }
+}
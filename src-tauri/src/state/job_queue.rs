@@ -0,0 +1,615 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::model::{ConversionJob, ConvertRequest, ConvertSummary, JobId, JobStatus};
+
+use super::queue_store::{save_snapshot, QueueSnapshot};
+use super::stop_reason::StopReason;
+use super::types::JobQueue;
+
+const JOB_UPDATED_EVENT: &str = "job-updated";
+const QUEUE_CHANGED_EVENT: &str = "queue-changed";
+const DEFAULT_CONCURRENCY_LIMIT: usize = 1;
+const MAX_CONCURRENCY_LIMIT: usize = 8;
+const INTERRUPTED_JOB_MESSAGE: &str = "The app closed before this job finished.";
+
+pub struct JobQueueState {
+    last_id: JobId,
+    jobs: Vec<ConversionJob>,
+    concurrency_limit: usize,
+    store_path: Option<PathBuf>,
+}
+
+impl Default for JobQueueState {
+    fn default() -> Self {
+        Self {
+            last_id: 0,
+            jobs: Vec::new(),
+            concurrency_limit: DEFAULT_CONCURRENCY_LIMIT,
+            store_path: None,
+        }
+    }
+}
+
+impl JobQueueState {
+    pub fn restore(snapshot: QueueSnapshot) -> Self {
+        let mut state = Self {
+            last_id: snapshot.last_id,
+            jobs: snapshot.jobs,
+            ..Self::default()
+        };
+        if state
+            .set_concurrency_limit(snapshot.concurrency_limit)
+            .is_err()
+        {
+            state.concurrency_limit = DEFAULT_CONCURRENCY_LIMIT;
+        }
+        state
+    }
+
+    pub fn snapshot(&self) -> QueueSnapshot {
+        QueueSnapshot {
+            last_id: self.last_id,
+            concurrency_limit: self.concurrency_limit,
+            jobs: self.jobs.clone(),
+        }
+    }
+
+    pub fn set_store_path(&mut self, path: Option<PathBuf>) {
+        self.store_path = path;
+    }
+
+    pub fn persist(&self) {
+        let Some(path) = self.store_path.as_deref() else {
+            return;
+        };
+        let _ = save_snapshot(path, &self.snapshot());
+    }
+
+    pub fn jobs(&self) -> &[ConversionJob] {
+        &self.jobs
+    }
+
+    pub fn reserve_id(&mut self) -> JobId {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    pub fn set_concurrency_limit(&mut self, limit: usize) -> Result<(), String> {
+        if !(1..=MAX_CONCURRENCY_LIMIT).contains(&limit) {
+            return Err(format!(
+                "Concurrency limit must be between 1 and {MAX_CONCURRENCY_LIMIT} (got {limit})."
+            ));
+        }
+        self.concurrency_limit = limit;
+        Ok(())
+    }
+
+    pub fn enqueue(&mut self, requests: Vec<ConvertRequest>) -> Vec<ConversionJob> {
+        let mut added = Vec::with_capacity(requests.len());
+        for request in requests {
+            let job = ConversionJob {
+                id: self.reserve_id(),
+                request,
+                status: JobStatus::Queued,
+                result: None,
+                error: None,
+                partial_output_path: None,
+            };
+            self.jobs.push(job.clone());
+            added.push(job);
+        }
+        added
+    }
+
+    pub fn remove(&mut self, id: JobId) -> Result<ConversionJob, String> {
+        let position = self.position(id)?;
+        if self.jobs[position].status == JobStatus::Running {
+            return Err(format!("Job {id} is running and cannot be removed."));
+        }
+        Ok(self.jobs.remove(position))
+    }
+
+    pub fn reorder(&mut self, ordered_ids: &[JobId]) -> Result<(), String> {
+        let unique_ids: HashSet<JobId> = ordered_ids.iter().copied().collect();
+        if unique_ids.len() != ordered_ids.len() {
+            return Err("Job order contains duplicate IDs.".to_string());
+        }
+
+        let mut reordered = Vec::with_capacity(self.jobs.len());
+        for id in ordered_ids {
+            reordered.push(self.jobs[self.position(*id)?].clone());
+        }
+        reordered.extend(
+            self.jobs
+                .iter()
+                .filter(|job| !unique_ids.contains(&job.id))
+                .cloned(),
+        );
+        self.jobs = reordered;
+        Ok(())
+    }
+
+    pub fn start_next(&mut self) -> Option<ConversionJob> {
+        let running_count = self
+            .jobs
+            .iter()
+            .filter(|job| job.status == JobStatus::Running)
+            .count();
+        if running_count >= self.concurrency_limit {
+            return None;
+        }
+
+        let job = self
+            .jobs
+            .iter_mut()
+            .find(|job| job.status == JobStatus::Queued)?;
+        job.status = JobStatus::Running;
+        Some(job.clone())
+    }
+
+    pub fn finish(
+        &mut self,
+        id: JobId,
+        status: JobStatus,
+        outcome: Result<ConvertSummary, String>,
+    ) -> Result<ConversionJob, String> {
+        let position = self.position(id)?;
+        let job = &mut self.jobs[position];
+        job.status = status;
+        job.partial_output_path = None;
+        match outcome {
+            Ok(summary) => job.result = Some(summary),
+            Err(error) => job.error = Some(error),
+        }
+        Ok(job.clone())
+    }
+
+    pub fn settle_queued(
+        &mut self,
+        id: JobId,
+        reason: StopReason,
+    ) -> Result<Option<ConversionJob>, String> {
+        let position = self.position(id)?;
+        let job = &mut self.jobs[position];
+        if job.status != JobStatus::Queued {
+            return Ok(None);
+        }
+        settle(job, reason);
+        Ok(Some(job.clone()))
+    }
+
+    pub fn settle_all_queued(&mut self, reason: StopReason) -> Vec<ConversionJob> {
+        self.jobs
+            .iter_mut()
+            .filter(|job| job.status == JobStatus::Queued)
+            .map(|job| {
+                settle(job, reason);
+                job.clone()
+            })
+            .collect()
+    }
+
+    pub fn is_partial_output_claimed(&self, id: JobId, output_path: &Path) -> bool {
+        self.jobs.iter().any(|job| {
+            job.id != id
+                && job.status == JobStatus::Running
+                && job.partial_output_path.as_deref().map(Path::new) == Some(output_path)
+        })
+    }
+
+    pub fn set_partial_output_path(&mut self, id: JobId, output_path: &Path) {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) {
+            job.partial_output_path = Some(output_path.to_string_lossy().to_string());
+        }
+    }
+
+    pub fn mark_interrupted(&mut self) -> Vec<ConversionJob> {
+        self.jobs
+            .iter_mut()
+            .filter(|job| matches!(job.status, JobStatus::Queued | JobStatus::Running))
+            .map(|job| {
+                let interrupted = job.clone();
+                job.status = JobStatus::Interrupted;
+                job.error = Some(INTERRUPTED_JOB_MESSAGE.to_string());
+                interrupted
+            })
+            .collect()
+    }
+
+    pub fn resume_interrupted(&mut self) -> Vec<ConversionJob> {
+        self.jobs
+            .iter_mut()
+            .filter(|job| job.status == JobStatus::Interrupted)
+            .map(|job| {
+                job.status = JobStatus::Queued;
+                job.error = None;
+                job.partial_output_path = None;
+                job.clone()
+            })
+            .collect()
+    }
+
+    pub fn discard_interrupted(&mut self) -> usize {
+        let before = self.jobs.len();
+        self.jobs.retain(|job| job.status != JobStatus::Interrupted);
+        before - self.jobs.len()
+    }
+
+    fn position(&self, id: JobId) -> Result<usize, String> {
+        self.jobs
+            .iter()
+            .position(|job| job.id == id)
+            .ok_or_else(|| format!("Job {id} not found."))
+    }
+}
+
+pub fn enqueue_jobs(
+    app: &AppHandle,
+    requests: Vec<ConvertRequest>,
+) -> Result<Vec<ConversionJob>, String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    let added = state.enqueue(requests);
+    state.persist();
+    drop(state);
+    queue.wakeup.notify_all();
+    emit_job_updates(app, &added);
+    Ok(added)
+}
+
+pub fn wait_for_job(app: &AppHandle, id: JobId) -> Result<ConvertSummary, String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    loop {
+        let job = state
+            .jobs()
+            .iter()
+            .find(|job| job.id == id)
+            .ok_or_else(|| format!("Job {id} was removed from the queue."))?;
+        if !matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+            return match (&job.result, &job.error) {
+                (Some(summary), _) => Ok(summary.clone()),
+                (None, Some(error)) => Err(error.clone()),
+                (None, None) => Err(format!("Job {id} ended without a result.")),
+            };
+        }
+        state = queue
+            .wakeup
+            .wait(state)
+            .map_err(|_| "Failed to wait on job queue.".to_string())?;
+    }
+}
+
+pub fn set_concurrency_limit(app: &AppHandle, limit: usize) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    state.set_concurrency_limit(limit)?;
+    state.persist();
+    drop(state);
+    queue.wakeup.notify_all();
+    Ok(())
+}
+
+pub fn list_jobs(app: &AppHandle) -> Result<Vec<ConversionJob>, String> {
+    let queue = app.state::<JobQueue>();
+    let state = lock_queue(&queue)?;
+    Ok(state.jobs().to_vec())
+}
+
+pub fn remove_job(app: &AppHandle, id: JobId) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    state.remove(id)?;
+    state.persist();
+    let jobs = state.jobs().to_vec();
+    drop(state);
+    queue.wakeup.notify_all();
+    emit_queue_changed(app, &jobs);
+    Ok(())
+}
+
+pub fn reorder_jobs(app: &AppHandle, ordered_ids: &[JobId]) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    state.reorder(ordered_ids)?;
+    state.persist();
+    let jobs = state.jobs().to_vec();
+    drop(state);
+    emit_queue_changed(app, &jobs);
+    Ok(())
+}
+
+pub fn reserve_partial_output<F>(app: &AppHandle, id: JobId, reserve: F) -> Result<(), String>
+where
+    F: FnOnce(&dyn Fn(&Path) -> bool) -> Result<PathBuf, String>,
+{
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    let output_path = reserve(&|path| state.is_partial_output_claimed(id, path))?;
+    state.set_partial_output_path(id, &output_path);
+    state.persist();
+    Ok(())
+}
+
+pub fn with_partial_output_claims<T, F>(app: &AppHandle, id: JobId, reserve: F) -> Result<T, String>
+where
+    F: FnOnce(&dyn Fn(&Path) -> bool) -> Result<T, String>,
+{
+    let queue = app.state::<JobQueue>();
+    let state = lock_queue(&queue)?;
+    reserve(&|path| state.is_partial_output_claimed(id, path))
+}
+
+pub fn close_job_queue_store(app: &AppHandle) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    lock_queue(&queue)?.set_store_path(None);
+    Ok(())
+}
+
+pub fn settle_queued_jobs(app: &AppHandle, reason: StopReason) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    let settled = state.settle_all_queued(reason);
+    state.persist();
+    drop(state);
+    queue.wakeup.notify_all();
+    emit_job_updates(app, &settled);
+    Ok(())
+}
+
+pub(super) fn settle_queued_job(
+    app: &AppHandle,
+    id: JobId,
+    reason: StopReason,
+) -> Result<bool, String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    let settled = state.settle_queued(id, reason)?;
+    state.persist();
+    drop(state);
+    queue.wakeup.notify_all();
+    let Some(job) = settled else {
+        return Ok(false);
+    };
+    emit_job_updates(app, std::slice::from_ref(&job));
+    Ok(true)
+}
+
+pub fn wait_for_next_job(app: &AppHandle) -> Result<ConversionJob, String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    loop {
+        if let Some(job) = state.start_next() {
+            state.persist();
+            drop(state);
+            emit_job_updates(app, std::slice::from_ref(&job));
+            return Ok(job);
+        }
+        state = queue
+            .wakeup
+            .wait(state)
+            .map_err(|_| "Failed to wait on job queue.".to_string())?;
+    }
+}
+
+pub fn finish_job(
+    app: &AppHandle,
+    id: JobId,
+    status: JobStatus,
+    outcome: Result<ConvertSummary, String>,
+) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    let job = state.finish(id, status, outcome)?;
+    state.persist();
+    drop(state);
+    queue.wakeup.notify_all();
+    emit_job_updates(app, std::slice::from_ref(&job));
+    Ok(())
+}
+
+fn settle(job: &mut ConversionJob, reason: StopReason) {
+    job.status = reason.status();
+    job.error = Some(reason.error());
+}
+
+pub(super) fn lock_queue(
+    queue: &JobQueue,
+) -> Result<std::sync::MutexGuard<'_, JobQueueState>, String> {
+    queue
+        .state
+        .lock()
+        .map_err(|_| "Failed to lock job queue.".to_string())
+}
+
+pub(super) fn emit_job_updates(app: &AppHandle, jobs: &[ConversionJob]) {
+    for job in jobs {
+        let _ = app.emit(JOB_UPDATED_EVENT, job.clone());
+    }
+}
+
+fn emit_queue_changed(app: &AppHandle, jobs: &[ConversionJob]) {
+    let _ = app.emit(QUEUE_CHANGED_EVENT, jobs.to_vec());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JobQueueState;
+    use crate::model::{ConvertRequest, JobStatus};
+    use crate::state::stop_reason::StopReason;
+
+    fn request(input_path: &str) -> ConvertRequest {
+        ConvertRequest {
+            input_path: input_path.to_string(),
+            av1_encoder: None,
+            target_policy: Default::default(),
+            quality_level: None,
+            preset_level: None,
+            vmaf_target: None,
+            two_pass: false,
+            max_encode_attempts: None,
+            collision_policy: Default::default(),
+            output_dir: None,
+            output_template: None,
+            source_root: None,
+        }
+    }
+
+    fn queue_of(paths: &[&str]) -> JobQueueState {
+        let mut state = JobQueueState::default();
+        state.enqueue(paths.iter().map(|path| request(path)).collect());
+        state
+    }
+
+    fn ids(state: &JobQueueState) -> Vec<u64> {
+        state.jobs().iter().map(|job| job.id).collect()
+    }
+
+    #[test]
+    fn enqueue_assigns_stable_increasing_ids() {
+        let mut state = queue_of(&["a.mkv", "b.mkv"]);
+        state.remove(2).expect("queued job should be removable");
+        let added = state.enqueue(vec![request("c.mkv")]);
+
+        assert_eq!(added[0].id, 3);
+        assert_eq!(ids(&state), [1, 3]);
+    }
+
+    #[test]
+    fn start_next_runs_jobs_in_queue_order() {
+        let mut state = queue_of(&["a.mkv", "b.mkv", "c.mkv"]);
+        state
+            .reorder(&[3, 1])
+            .expect("known job IDs should reorder");
+
+        let first = state.start_next().expect("a job should be queued");
+        assert_eq!(first.id, 3);
+        assert_eq!(first.status, JobStatus::Running);
+        assert_eq!(ids(&state), [3, 1, 2]);
+    }
+
+    #[test]
+    fn start_next_respects_concurrency_limit() {
+        let mut state = queue_of(&["a.mkv", "b.mkv", "c.mkv"]);
+        state
+            .set_concurrency_limit(2)
+            .expect("limit within range should apply");
+
+        assert_eq!(state.start_next().map(|job| job.id), Some(1));
+        assert_eq!(state.start_next().map(|job| job.id), Some(2));
+        assert!(state.start_next().is_none());
+
+        state
+            .finish(1, JobStatus::Failed, Err("boom".to_string()))
+            .expect("running job should finish");
+        assert_eq!(state.start_next().map(|job| job.id), Some(3));
+    }
+
+    #[test]
+    fn set_concurrency_limit_rejects_out_of_range_values() {
+        let mut state = JobQueueState::default();
+
+        assert!(state.set_concurrency_limit(0).is_err());
+        assert!(state.set_concurrency_limit(9).is_err());
+    }
+
+    #[test]
+    fn remove_rejects_running_job() {
+        let mut state = queue_of(&["a.mkv"]);
+        state.start_next();
+
+        assert!(state.remove(1).is_err());
+    }
+
+    #[test]
+    fn reorder_rejects_unknown_and_duplicate_ids() {
+        let mut state = queue_of(&["a.mkv", "b.mkv"]);
+
+        assert!(state.reorder(&[1, 1]).is_err());
+        assert!(state.reorder(&[7]).is_err());
+        assert_eq!(ids(&state), [1, 2]);
+    }
+
+    #[test]
+    fn settle_all_queued_leaves_running_job_alone() {
+        let mut state = queue_of(&["a.mkv", "b.mkv"]);
+        state.start_next();
+        let stopped = state.settle_all_queued(StopReason::Stopped);
+
+        assert_eq!(stopped.len(), 1);
+        assert_eq!(state.jobs()[0].status, JobStatus::Running);
+        assert_eq!(state.jobs()[1].status, JobStatus::Stopped);
+        assert_eq!(
+            state.jobs()[1]
+                .error
+                .as_deref()
+                .and_then(StopReason::from_error),
+            Some(StopReason::Stopped)
+        );
+    }
+
+    #[test]
+    fn settle_queued_ignores_running_job() {
+        let mut state = queue_of(&["a.mkv", "b.mkv"]);
+        state.start_next();
+
+        let running = state
+            .settle_queued(1, StopReason::Skipped)
+            .expect("known job should settle");
+        let queued = state
+            .settle_queued(2, StopReason::Skipped)
+            .expect("known job should settle");
+
+        assert!(running.is_none());
+        assert_eq!(queued.map(|job| job.status), Some(JobStatus::Skipped));
+    }
+
+    #[test]
+    fn mark_interrupted_keeps_finished_jobs() {
+        let mut state = queue_of(&["a.mkv", "b.mkv", "c.mkv"]);
+        state.start_next();
+        state
+            .finish(1, JobStatus::Failed, Err("boom".to_string()))
+            .expect("running job should finish");
+        state.start_next();
+
+        let interrupted = state.mark_interrupted();
+
+        assert_eq!(
+            interrupted.iter().map(|job| job.status).collect::<Vec<_>>(),
+            [JobStatus::Running, JobStatus::Queued]
+        );
+        assert_eq!(state.jobs()[0].status, JobStatus::Failed);
+        assert_eq!(state.jobs()[1].status, JobStatus::Interrupted);
+        assert_eq!(state.jobs()[2].status, JobStatus::Interrupted);
+    }
+
+    #[test]
+    fn restored_interrupted_jobs_resume_in_order() {
+        let mut state = queue_of(&["a.mkv", "b.mkv"]);
+        state.start_next();
+        state.set_partial_output_path(1, std::path::Path::new("a.av1.partial.mp4"));
+
+        let mut restored = JobQueueState::restore(state.snapshot());
+        restored.mark_interrupted();
+        let resumed = restored.resume_interrupted();
+
+        assert_eq!(resumed.len(), 2);
+        assert!(resumed.iter().all(|job| job.partial_output_path.is_none()));
+        assert_eq!(restored.start_next().map(|job| job.id), Some(1));
+        assert_eq!(restored.enqueue(vec![request("c.mkv")])[0].id, 3);
+    }
+
+    #[test]
+    fn discard_interrupted_removes_only_interrupted_jobs() {
+        let mut state = queue_of(&["a.mkv"]);
+        state.mark_interrupted();
+        state.enqueue(vec![request("b.mkv")]);
+
+        assert_eq!(state.discard_interrupted(), 1);
+        assert_eq!(ids(&state), [2]);
+    }
+}
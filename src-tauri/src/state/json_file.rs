@@ -0,0 +1,40 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+pub fn app_data_file_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data folder: {e}"))?;
+    Ok(data_dir.join(file_name))
+}
+
+pub fn read_json_file<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.to_string_lossy()))?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {e}", path.to_string_lossy()))
+}
+
+pub fn write_json_atomically<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.to_string_lossy()))?;
+    }
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {e}", path.to_string_lossy()))?;
+
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, text)
+        .map_err(|e| format!("Failed to write {}: {e}", temp_path.to_string_lossy()))?;
+    fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed to replace {}: {e}", path.to_string_lossy()))
+}
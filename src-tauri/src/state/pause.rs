@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::model::JobId;
+
+use super::process_registry::{collect_active_conversions, get_active_conversion};
+use super::types::ConversionControl;
+
+pub fn pause_conversion(app: &AppHandle, job_id: Option<JobId>) -> Result<(), String> {
+    for control in target_conversions(app, job_id)? {
+        if control.pause()? {
+            emit_paused_state(app, &control);
+        }
+    }
+    Ok(())
+}
+
+pub fn resume_conversion(app: &AppHandle, job_id: Option<JobId>) -> Result<(), String> {
+    for control in target_conversions(app, job_id)? {
+        if control.resume()? {
+            emit_paused_state(app, &control);
+        }
+    }
+    Ok(())
+}
+
+fn target_conversions(
+    app: &AppHandle,
+    job_id: Option<JobId>,
+) -> Result<Vec<Arc<ConversionControl>>, String> {
+    match job_id {
+        Some(job_id) => Ok(vec![get_active_conversion(app, job_id)?]),
+        None => collect_active_conversions(app),
+    }
+}
+
+fn emit_paused_state(app: &AppHandle, control: &ConversionControl) {
+    let Some(mut event) = control.last_progress() else {
+        return;
+    };
+    event.paused = control.is_paused();
+    if event.paused {
+        event.eta_seconds = None;
+    }
+    let _ = app.emit("convert-progress", event);
+}
@@ -1,9 +1,9 @@
-use std::process::ChildStdin;
 use std::sync::Arc;
 
 use tauri::{AppHandle, Manager};
 
 use crate::error_protocol::error_no_active_conversion;
+use crate::model::JobId;
 
 use super::types::{ActiveConversionControl, ActiveFfmpegPids, ConversionControl};
 
@@ -29,78 +29,55 @@ pub fn unregister_ffmpeg_pid(app: &AppHandle, pid: u32) -> Result<(), String> {
 
 pub fn register_active_conversion(
     app: &AppHandle,
-    pid: u32,
-    stdin: ChildStdin,
-) -> Result<(), String> {
+    job_id: JobId,
+) -> Result<Arc<ConversionControl>, String> {
     let state = app.state::<ActiveConversionControl>();
     let mut active = state
         .0
         .lock()
         .map_err(|_| "Failed to lock active conversion state.".to_string())?;
-    if active.is_some() {
-        return Err("Another conversion is already running.".to_string());
+    if active.contains_key(&job_id) {
+        return Err(format!("Job {job_id} is already running."));
     }
-    *active = Some(Arc::new(ConversionControl::new(pid, stdin)));
-    Ok(())
+    let control = Arc::new(ConversionControl::default());
+    active.insert(job_id, Arc::clone(&control));
+    Ok(control)
 }
 
-pub fn unregister_active_conversion(app: &AppHandle, pid: u32) -> Result<(), String> {
+pub fn unregister_active_conversion(app: &AppHandle, job_id: JobId) -> Result<(), String> {
     let state = app.state::<ActiveConversionControl>();
     let mut active = state
         .0
         .lock()
         .map_err(|_| "Failed to lock active conversion state.".to_string())?;
-    let should_clear = active
-        .as_ref()
-        .map(|control| control.pid() == pid)
-        .unwrap_or(false);
-    if should_clear {
-        *active = None;
-    }
+    active.remove(&job_id);
     Ok(())
 }
 
-pub(super) fn get_active_conversion(app: &AppHandle) -> Result<Arc<ConversionControl>, String> {
+pub fn get_active_conversion(
+    app: &AppHandle,
+    job_id: JobId,
+) -> Result<Arc<ConversionControl>, String> {
     let state = app.state::<ActiveConversionControl>();
     let active = state
         .0
         .lock()
         .map_err(|_| "Failed to lock active conversion state.".to_string())?;
     active
-        .as_ref()
+        .get(&job_id)
         .cloned()
         .ok_or_else(error_no_active_conversion)
 }
 
-pub(super) fn set_active_conversion_cancel_requested(
+pub(super) fn collect_active_conversions(
     app: &AppHandle,
-    requested: bool,
-) -> Result<(), String> {
-    let state = app.state::<ActiveConversionControl>();
-    let active = state
-        .0
-        .lock()
-        .map_err(|_| "Failed to lock active conversion state.".to_string())?;
-    if let Some(control) = active.as_ref() {
-        if requested {
-            control.mark_cancel_requested();
-        } else {
-            control.clear_cancel_requested();
-        }
-    }
-    Ok(())
-}
-
-pub(super) fn is_active_conversion_cancel_requested(app: &AppHandle) -> Result<bool, String> {
+) -> Result<Vec<Arc<ConversionControl>>, String> {
     let state = app.state::<ActiveConversionControl>();
     let active = state
         .0
         .lock()
         .map_err(|_| "Failed to lock active conversion state.".to_string())?;
-    Ok(active
-        .as_ref()
-        .map(|control| control.is_cancel_requested())
-        .unwrap_or(false))
+    Ok(active.values().cloned().collect())
 }
 
 pub(super) fn collect_registered_ffmpeg_pids(app: &AppHandle) -> Result<Vec<u32>, String> {
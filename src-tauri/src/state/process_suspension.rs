@@ -0,0 +1,85 @@
+#[cfg(not(windows))]
+use std::process::Command;
+
+#[cfg(windows)]
+mod ntdll {
+    use std::ffi::c_void;
+
+    pub const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn OpenProcess(
+            desired_access: u32,
+            inherit_handle: i32,
+            process_id: u32,
+        ) -> *mut c_void;
+        pub fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        pub fn NtSuspendProcess(process: *mut c_void) -> i32;
+        pub fn NtResumeProcess(process: *mut c_void) -> i32;
+    }
+}
+
+pub(super) fn suspend_process(pid: u32) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        with_process_handle(pid, |handle| unsafe { ntdll::NtSuspendProcess(handle) }).map_err(
+            |status| format!("Failed to suspend ffmpeg process {pid} (NTSTATUS {status:#x})."),
+        )
+    }
+
+    #[cfg(not(windows))]
+    {
+        send_signal(pid, "-STOP")
+            .map_err(|e| format!("Failed to suspend ffmpeg process {pid}: {e}"))
+    }
+}
+
+pub(super) fn resume_process(pid: u32) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        with_process_handle(pid, |handle| unsafe { ntdll::NtResumeProcess(handle) }).map_err(
+            |status| format!("Failed to resume ffmpeg process {pid} (NTSTATUS {status:#x})."),
+        )
+    }
+
+    #[cfg(not(windows))]
+    {
+        send_signal(pid, "-CONT").map_err(|e| format!("Failed to resume ffmpeg process {pid}: {e}"))
+    }
+}
+
+#[cfg(windows)]
+fn with_process_handle(
+    pid: u32,
+    operation: impl FnOnce(*mut std::ffi::c_void) -> i32,
+) -> Result<(), i32> {
+    let handle = unsafe { ntdll::OpenProcess(ntdll::PROCESS_SUSPEND_RESUME, 0, pid) };
+    if handle.is_null() {
+        return Err(-1);
+    }
+    let status = operation(handle);
+    unsafe {
+        ntdll::CloseHandle(handle);
+    }
+    if status < 0 {
+        return Err(status);
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn send_signal(pid: u32, signal: &str) -> Result<(), String> {
+    let status = Command::new("kill")
+        .args([signal, &pid.to_string()])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("kill {signal} exited with {status}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+
+use tauri::{AppHandle, Manager};
+
+use crate::model::{ConversionJob, JobStatus};
+
+use super::job_queue::{emit_job_updates, lock_queue, JobQueueState};
+use super::queue_store::{load_snapshot, queue_file_path};
+use super::types::{JobQueue, QueueResetNotice};
+
+pub fn restore_job_queue(app: &AppHandle) {
+    if let Err(error) = load_job_queue(app) {
+        let _ = set_queue_reset_notice(
+            app,
+            format!("The saved job queue could not be restored: {error}"),
+        );
+    }
+}
+
+pub fn take_queue_reset_notice(app: &AppHandle) -> Result<Option<String>, String> {
+    Ok(lock_queue_reset_notice(app)?.take())
+}
+
+fn load_job_queue(app: &AppHandle) -> Result<(), String> {
+    let path = queue_file_path(app)?;
+    let mut restored = match load_snapshot(&path) {
+        Ok(Some(snapshot)) => JobQueueState::restore(snapshot),
+        Ok(None) => JobQueueState::default(),
+        Err(error) => {
+            set_queue_reset_notice(
+                app,
+                format!("The saved job queue could not be read, so it starts empty: {error}"),
+            )?;
+            JobQueueState::default()
+        }
+    };
+
+    for job in restored.mark_interrupted() {
+        if job.status == JobStatus::Running {
+            remove_partial_output(&job);
+        }
+    }
+    restored.set_store_path(Some(path));
+    restored.persist();
+
+    let queue = app.state::<JobQueue>();
+    *lock_queue(&queue)? = restored;
+    Ok(())
+}
+
+pub fn resume_interrupted_jobs(app: &AppHandle) -> Result<Vec<ConversionJob>, String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    let resumed = state.resume_interrupted();
+    state.persist();
+    drop(state);
+    queue.wakeup.notify_all();
+    emit_job_updates(app, &resumed);
+    Ok(resumed)
+}
+
+pub fn discard_interrupted_jobs(app: &AppHandle) -> Result<(), String> {
+    let queue = app.state::<JobQueue>();
+    let mut state = lock_queue(&queue)?;
+    if state.discard_interrupted() > 0 {
+        state.persist();
+    }
+    Ok(())
+}
+
+fn remove_partial_output(job: &ConversionJob) {
+    let Some(output_path) = job.partial_output_path.as_deref() else {
+        return;
+    };
+    let output_path = Path::new(output_path);
+    if !output_path.exists() {
+        return;
+    }
+    let _ = fs::remove_file(output_path);
+}
+
+fn set_queue_reset_notice(app: &AppHandle, notice: String) -> Result<(), String> {
+    *lock_queue_reset_notice(app)? = Some(notice);
+    Ok(())
+}
+
+fn lock_queue_reset_notice(
+    app: &AppHandle,
+) -> Result<std::sync::MutexGuard<'_, Option<String>>, String> {
+    app.state::<QueueResetNotice>()
+        .inner()
+        .0
+        .lock()
+        .map_err(|_| "Failed to lock queue reset notice.".to_string())
+}
@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::model::{ConversionJob, JobId};
+
+use super::json_file::{app_data_file_path, read_json_file, write_json_atomically};
+
+const QUEUE_FILE_NAME: &str = "job-queue.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSnapshot {
+    pub last_id: JobId,
+    pub concurrency_limit: usize,
+    pub jobs: Vec<ConversionJob>,
+}
+
+pub fn queue_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app_data_file_path(app, QUEUE_FILE_NAME)
+}
+
+pub fn load_snapshot(path: &Path) -> Result<Option<QueueSnapshot>, String> {
+    read_json_file(path)
+}
+
+pub fn save_snapshot(path: &Path, snapshot: &QueueSnapshot) -> Result<(), String> {
+    write_json_atomically(path, snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_snapshot, save_snapshot, QueueSnapshot};
+    use crate::test_fs::scratch_dir;
+
+    #[test]
+    fn snapshot_round_trips_through_disk() {
+        let dir = scratch_dir("queue-store");
+        let path = dir.join("job-queue.json");
+        let snapshot = QueueSnapshot {
+            last_id: 4,
+            concurrency_limit: 2,
+            jobs: Vec::new(),
+        };
+
+        save_snapshot(&path, &snapshot).expect("snapshot should save");
+        let loaded = load_snapshot(&path)
+            .expect("snapshot should load")
+            .expect("snapshot file should exist");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.last_id, 4);
+        assert_eq!(loaded.concurrency_limit, 2);
+        assert!(load_snapshot(&path)
+            .expect("missing file is not an error")
+            .is_none());
+    }
+}
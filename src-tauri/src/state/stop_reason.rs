@@ -0,0 +1,61 @@
+use crate::error_protocol::{
+    error_canceled_by_user, error_skipped_by_user, error_stopped_by_user, is_error_code,
+    ERROR_CODE_CANCELED_BY_USER, ERROR_CODE_SKIPPED_BY_USER, ERROR_CODE_STOPPED_BY_USER,
+};
+use crate::model::JobStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Canceled,
+    Skipped,
+    Stopped,
+}
+
+impl StopReason {
+    pub fn from_error(error: &str) -> Option<Self> {
+        [Self::Canceled, Self::Skipped, Self::Stopped]
+            .into_iter()
+            .find(|reason| is_error_code(error, reason.error_code()))
+    }
+
+    pub fn status(self) -> JobStatus {
+        match self {
+            Self::Canceled => JobStatus::Canceled,
+            Self::Skipped => JobStatus::Skipped,
+            Self::Stopped => JobStatus::Stopped,
+        }
+    }
+
+    pub fn error(self) -> String {
+        match self {
+            Self::Canceled => error_canceled_by_user(),
+            Self::Skipped => error_skipped_by_user(),
+            Self::Stopped => error_stopped_by_user(),
+        }
+    }
+
+    fn error_code(self) -> &'static str {
+        match self {
+            Self::Canceled => ERROR_CODE_CANCELED_BY_USER,
+            Self::Skipped => ERROR_CODE_SKIPPED_BY_USER,
+            Self::Stopped => ERROR_CODE_STOPPED_BY_USER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StopReason;
+
+    #[test]
+    fn from_error_recognizes_each_reason() {
+        for reason in [
+            StopReason::Canceled,
+            StopReason::Skipped,
+            StopReason::Stopped,
+        ] {
+            assert_eq!(StopReason::from_error(&reason.error()), Some(reason));
+        }
+        assert_eq!(StopReason::from_error("ffmpeg failed: boom"), None);
+    }
+}
@@ -5,11 +5,11 @@ use tauri::AppHandle;
 
 use super::cancellation::request_cancel;
 use super::process_registry::{
-    clear_registered_ffmpeg_pids, collect_registered_ffmpeg_pids,
-    set_active_conversion_cancel_requested,
+    clear_registered_ffmpeg_pids, collect_active_conversions, collect_registered_ffmpeg_pids,
 };
+use super::stop_reason::StopReason;
 
-fn terminate_pid(pid: u32) {
+pub(super) fn terminate_pid(pid: u32) {
     #[cfg(windows)]
     {
         use crate::ffmpeg::hidden_program_command;
@@ -28,8 +28,15 @@ fn terminate_pid(pid: u32) {
 
 pub fn terminate_all_active_ffmpeg(app: &AppHandle) -> Result<(), String> {
     request_cancel(app);
-    set_active_conversion_cancel_requested(app, true)?;
+    for control in collect_active_conversions(app)? {
+        control.request_stop(StopReason::Canceled);
+        let _ = control.resume();
+    }
+
+    terminate_registered_ffmpeg(app)
+}
 
+pub(super) fn terminate_registered_ffmpeg(app: &AppHandle) -> Result<(), String> {
     let pids = collect_registered_ffmpeg_pids(app)?;
     for pid in pids {
         terminate_pid(pid);
@@ -1,50 +1,150 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::process::ChildStdin;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::model::{EncoderCheck, JobId, ProgressEvent};
+
+use super::job_queue::JobQueueState;
+use super::process_suspension::{resume_process, suspend_process};
+use super::stop_reason::StopReason;
 
 #[derive(Default)]
 pub struct ActiveFfmpegPids(pub Mutex<HashSet<u32>>);
 
 #[derive(Default)]
-pub struct Av1EncoderCache(pub Mutex<Option<Vec<String>>>);
+pub struct Av1EncoderCache(pub Mutex<Option<Vec<EncoderCheck>>>);
 
 #[derive(Default)]
-pub struct ActiveConversionControl(pub Mutex<Option<Arc<ConversionControl>>>);
+pub struct ActiveConversionControl(pub Mutex<HashMap<JobId, Arc<ConversionControl>>>);
 
 #[derive(Default)]
 pub struct GlobalCancelFlag(pub AtomicBool);
 
+#[derive(Default)]
+pub struct QueueResetNotice(pub Mutex<Option<String>>);
+
+#[derive(Default)]
+pub struct JobQueue {
+    pub state: Mutex<JobQueueState>,
+    pub wakeup: Condvar,
+}
+
+#[derive(Default)]
 pub struct ConversionControl {
+    process: Mutex<Option<FfmpegProcess>>,
+    stop_reason: Mutex<Option<StopReason>>,
+    pause_clock: Mutex<PauseClock>,
+    last_progress: Mutex<Option<ProgressEvent>>,
+}
+
+struct FfmpegProcess {
     pid: u32,
-    stdin: Mutex<ChildStdin>,
-    cancel_requested: AtomicBool,
+    stdin: ChildStdin,
+}
+
+#[derive(Default)]
+struct PauseClock {
+    paused_since: Option<Instant>,
+    paused_total: Duration,
 }
 
 impl ConversionControl {
-    pub fn new(pid: u32, stdin: ChildStdin) -> Self {
-        Self {
-            pid,
-            stdin: Mutex::new(stdin),
-            cancel_requested: AtomicBool::new(false),
+    pub fn attach_process(&self, pid: u32, stdin: ChildStdin) -> Result<(), String> {
+        let mut process = self.lock_process()?;
+        if process.is_some() {
+            return Err("Another ffmpeg process is already running for this job.".to_string());
+        }
+        if self.is_paused() {
+            suspend_process(pid)?;
+        }
+        *process = Some(FfmpegProcess { pid, stdin });
+        Ok(())
+    }
+
+    pub fn pause(&self) -> Result<bool, String> {
+        let process = self.lock_process()?;
+        let mut clock = self.lock_pause_clock()?;
+        if clock.paused_since.is_some() {
+            return Ok(false);
+        }
+        if let Some(process) = process.as_ref() {
+            suspend_process(process.pid)?;
+        }
+        clock.paused_since = Some(Instant::now());
+        Ok(true)
+    }
+
+    pub fn resume(&self) -> Result<bool, String> {
+        let process = self.lock_process()?;
+        let mut clock = self.lock_pause_clock()?;
+        let Some(paused_since) = clock.paused_since else {
+            return Ok(false);
+        };
+        if let Some(process) = process.as_ref() {
+            resume_process(process.pid)?;
         }
+        clock.paused_since = None;
+        clock.paused_total += paused_since.elapsed();
+        Ok(true)
     }
 
-    pub fn pid(&self) -> u32 {
-        self.pid
+    pub fn is_paused(&self) -> bool {
+        self.lock_pause_clock()
+            .map(|clock| clock.paused_since.is_some())
+            .unwrap_or(false)
     }
 
-    pub fn mark_cancel_requested(&self) {
-        self.cancel_requested.store(true, Ordering::Relaxed);
+    pub fn paused_duration(&self) -> Duration {
+        self.lock_pause_clock()
+            .map(|clock| {
+                clock.paused_total
+                    + clock
+                        .paused_since
+                        .map(|paused_since| paused_since.elapsed())
+                        .unwrap_or_default()
+            })
+            .unwrap_or_default()
     }
 
-    pub fn clear_cancel_requested(&self) {
-        self.cancel_requested.store(false, Ordering::Relaxed);
+    pub fn record_progress(&self, event: &ProgressEvent) {
+        if let Ok(mut last_progress) = self.last_progress.lock() {
+            *last_progress = Some(event.clone());
+        }
+    }
+
+    pub fn last_progress(&self) -> Option<ProgressEvent> {
+        self.last_progress
+            .lock()
+            .ok()
+            .and_then(|last_progress| last_progress.clone())
+    }
+
+    pub fn detach_process(&self, pid: u32) -> Result<(), String> {
+        let mut process = self.lock_process()?;
+        if process.as_ref().is_some_and(|attached| attached.pid == pid) {
+            *process = None;
+        }
+        Ok(())
+    }
+
+    pub fn process_pid(&self) -> Result<Option<u32>, String> {
+        Ok(self.lock_process()?.as_ref().map(|process| process.pid))
+    }
+
+    pub fn request_stop(&self, reason: StopReason) {
+        if let Ok(mut stop_reason) = self.stop_reason.lock() {
+            stop_reason.get_or_insert(reason);
+        }
     }
 
-    pub fn is_cancel_requested(&self) -> bool {
-        self.cancel_requested.load(Ordering::Relaxed)
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+            .lock()
+            .map(|stop_reason| *stop_reason)
+            .unwrap_or(Some(StopReason::Canceled))
     }
 
     pub fn send_quit_command(&self) -> Result<(), String> {
@@ -52,16 +152,30 @@ impl ConversionControl {
     }
 
     fn send_stdin_command(&self, command: &[u8]) -> Result<(), String> {
-        let mut stdin = self
+        let mut process = self.lock_process()?;
+        let Some(process) = process.as_mut() else {
+            return Ok(());
+        };
+        process
             .stdin
-            .lock()
-            .map_err(|_| "Failed to lock conversion stdin.".to_string())?;
-        stdin
             .write_all(command)
             .map_err(|e| format!("Failed to send command to ffmpeg: {e}"))?;
-        stdin
+        process
+            .stdin
             .flush()
             .map_err(|e| format!("Failed to flush ffmpeg command: {e}"))?;
         Ok(())
     }
+
+    fn lock_pause_clock(&self) -> Result<std::sync::MutexGuard<'_, PauseClock>, String> {
+        self.pause_clock
+            .lock()
+            .map_err(|_| "Failed to lock conversion pause state.".to_string())
+    }
+
+    fn lock_process(&self) -> Result<std::sync::MutexGuard<'_, Option<FfmpegProcess>>, String> {
+        self.process
+            .lock()
+            .map_err(|_| "Failed to lock conversion process.".to_string())
+    }
 }
@@ -0,0 +1,8 @@
+use std::fs;
+use std::path::PathBuf;
+
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vid2av1-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("scratch dir should be created");
+    dir
+}
@@ -1,6 +1,15 @@
 import type { AppElements } from "./dom.js";
-import { isCancellationErrorMessage, isNoActiveConversionErrorMessage } from "./conversion-errors.js";
-import { basename, toErrorMessage, toRawErrorMessage } from "./format.js";
+import { isNoActiveConversionErrorMessage } from "./conversion-errors.js";
+import {
+  basename,
+  formatEncoderCheck,
+  formatMediaSummary,
+  formatPlannedOutput,
+  formatPresetLevel,
+  formatTargetPolicy,
+  toErrorMessage,
+  toRawErrorMessage
+} from "./format.js";
 import { ProgressPresenter } from "./progress-presenter.js";
 import {
   deduplicatePaths,
@@ -9,22 +18,53 @@ import {
   remainingQueueCount
 } from "./queue-utils.js";
 import type { AppTauriApi } from "./tauri-api.js";
-import type { ConvertProgressPayload, ConvertRequest } from "./types.js";
+import type {
+  ConversionJob,
+  ConvertProgressPayload,
+  ConvertRequest,
+  ConvertResult,
+  JobStatus
+} from "./types.js";
 import { UiState } from "./ui-state.js";
 
 type EncoderStatusTone = "info" | "ok" | "warn";
 
+interface SourceTreeSelection {
+  sourceRoot: string;
+  outputDir: string;
+}
+
+interface JobBatch {
+  jobIds: number[];
+  pendingJobIds: Set<number>;
+  lastStatuses: Map<number, JobStatus>;
+  successCount: number;
+  failureCount: number;
+  skippedCount: number;
+  stoppedCount: number;
+}
+
+function isFinishedJob(job: ConversionJob): boolean {
+  return job.status !== "queued" && job.status !== "running";
+}
+
 export class ConvertApp {
   private readonly uiState: UiState;
   private readonly progressPresenter: ProgressPresenter;
   private isConverting = false;
   private cancelRequested = false;
+  private stopAfterCurrentRequested = false;
+  private isPaused = false;
   private queuedInputPaths: string[] = [];
+  private sourceTree: SourceTreeSelection | null = null;
   private hasAvailableEncoder = false;
   private isLoadingEncoder = false;
   private encoderLoadPromise: Promise<void> | null = null;
   private selectedAv1Encoder: string | null = null;
   private detachProgressListener: (() => void | Promise<void>) | null = null;
+  private detachJobListener: (() => void | Promise<void>) | null = null;
+  private detachQueueListener: (() => void | Promise<void>) | null = null;
+  private batch: JobBatch | null = null;
 
   constructor(
     private readonly elements: AppElements,
@@ -37,6 +77,8 @@ export class ConvertApp {
   async initialize(): Promise<void> {
     this.registerEventHandlers();
     void this.attachProgressListener();
+    void this.attachJobListener().then(() => this.resumeQueuedJobs());
+    void this.attachQueueListener();
     this.setEncoderStatus("Detecting AV1 encoder...", "info");
     void this.ensureEncodersLoaded(false);
     this.uiState.appendStatus("Ready.");
@@ -45,8 +87,14 @@ export class ConvertApp {
 
   private registerEventHandlers(): void {
     this.elements.browseInputButton.addEventListener("click", () => void this.handleBrowseInput());
+    this.elements.browseFolderButton.addEventListener("click", () => void this.handleBrowseFolder());
+    this.elements.recheckEncodersButton.addEventListener("click", () => void this.handleRecheckEncoders());
     this.elements.convertButton.addEventListener("click", () => void this.handleConvert());
     this.elements.cancelButton.addEventListener("click", () => void this.handleCancel());
+    this.elements.skipCurrentButton.addEventListener("click", () => void this.handleSkipCurrent());
+    this.elements.stopAfterCurrentButton.addEventListener("click", () => void this.handleStopAfterCurrent());
+    this.elements.pauseResumeButton.addEventListener("click", () => void this.handleTogglePause());
+    this.elements.presetLevel.addEventListener("input", () => this.syncPresetLabel());
     window.addEventListener("beforeunload", () => {
       this.teardownProgressListener();
     });
@@ -66,7 +114,39 @@ export class ConvertApp {
     }
   }
 
+  private async attachJobListener(): Promise<void> {
+    try {
+      const unlisten = await this.tauriApi.listenJobUpdates((job) => this.handleJobUpdate(job));
+      this.detachJobListener = () => {
+        void unlisten();
+        this.detachJobListener = null;
+      };
+    } catch (error) {
+      this.uiState.appendStatus(`Queue listener unavailable: ${toErrorMessage(error)}`);
+    }
+  }
+
+  private async attachQueueListener(): Promise<void> {
+    try {
+      const unlisten = await this.tauriApi.listenQueueChanges((jobs) => this.handleQueueChange(jobs));
+      this.detachQueueListener = () => {
+        void unlisten();
+        this.detachQueueListener = null;
+      };
+    } catch (error) {
+      this.uiState.appendStatus(`Queue listener unavailable: ${toErrorMessage(error)}`);
+    }
+  }
+
   private teardownProgressListener(): void {
+    if (this.detachJobListener) {
+      void this.detachJobListener();
+    }
+
+    if (this.detachQueueListener) {
+      void this.detachQueueListener();
+    }
+
     if (!this.detachProgressListener) {
       return;
     }
@@ -109,6 +189,30 @@ export class ConvertApp {
     }
   }
 
+  private async handleRecheckEncoders(): Promise<void> {
+    if (this.isConverting || this.isLoadingEncoder) {
+      return;
+    }
+
+    this.isLoadingEncoder = true;
+    this.setEncoderStatus("Re-checking AV1 encoders...", "info");
+    this.syncButtonState();
+
+    try {
+      const checks = await this.tauriApi.refreshEncoders();
+      for (const check of checks) {
+        this.uiState.appendStatus(formatEncoderCheck(check));
+      }
+    } catch (error) {
+      this.uiState.appendStatus(`Encoder re-check failed: ${toErrorMessage(error)}`);
+    } finally {
+      this.isLoadingEncoder = false;
+    }
+
+    this.hasAvailableEncoder = false;
+    await this.ensureEncodersLoaded(true);
+  }
+
   private async handleBrowseInput(): Promise<void> {
     if (this.isConverting) {
       return;
@@ -121,16 +225,70 @@ export class ConvertApp {
       }
 
       const normalizedInputPaths = deduplicatePaths(inputPaths);
+      this.sourceTree = null;
       this.queuedInputPaths = normalizedInputPaths;
       this.elements.inputPath.value = formatSelectedInputLabel(normalizedInputPaths);
       this.syncButtonState();
       this.uiState.appendStatus(`Queued ${formatFileCount(normalizedInputPaths.length)} for conversion.`);
-      void this.ensureEncodersLoaded(false);
+      void this.ensureEncodersLoaded(false).then(() => this.probeQueuedInputs(normalizedInputPaths));
     } catch (error) {
       this.uiState.appendStatus(`Browse input failed: ${toErrorMessage(error)}`);
     }
   }
 
+  private async handleBrowseFolder(): Promise<void> {
+    if (this.isConverting) {
+      return;
+    }
+
+    try {
+      const sourceRoot = await this.tauriApi.openFolderDialog("Folder to convert");
+      if (!sourceRoot) {
+        return;
+      }
+      const outputDir = await this.tauriApi.openFolderDialog("Destination for the converted tree");
+      if (!outputDir) {
+        return;
+      }
+
+      const scan = await this.tauriApi.scanFolders({ folders: [sourceRoot] });
+      for (const skipped of scan.skipped) {
+        this.uiState.appendStatus(`Not queued: ${basename(skipped.path)} (${skipped.reason})`);
+      }
+      const inputPaths = scan.files.map((file) => file.inputPath);
+      if (inputPaths.length === 0) {
+        this.uiState.appendStatus(`No video files found in ${sourceRoot}.`);
+        return;
+      }
+
+      this.sourceTree = { sourceRoot, outputDir };
+      this.queuedInputPaths = inputPaths;
+      this.elements.inputPath.value = `${sourceRoot} -> ${outputDir}`;
+      this.syncButtonState();
+      this.uiState.appendStatus(
+        `Queued ${formatFileCount(inputPaths.length)} from ${sourceRoot}, mirrored into ${outputDir}.`
+      );
+    } catch (error) {
+      this.uiState.appendStatus(`Browse folder failed: ${toErrorMessage(error)}`);
+    }
+  }
+
+  private async probeQueuedInputs(inputPaths: string[]): Promise<void> {
+    for (const inputPath of inputPaths) {
+      if (this.isConverting) {
+        return;
+      }
+
+      try {
+        const summary = await this.tauriApi.probeMedia(this.createConvertRequest(inputPath));
+        this.uiState.appendStatus(`${basename(inputPath)}: ${formatMediaSummary(summary)}`);
+        this.uiState.appendStatus(`Planned output: ${formatPlannedOutput(summary)}`);
+      } catch (error) {
+        this.uiState.appendStatus(`Probe failed for ${basename(inputPath)}: ${toErrorMessage(error)}`);
+      }
+    }
+  }
+
   private async handleConvert(): Promise<void> {
     if (this.isConverting) {
       return;
@@ -163,67 +321,212 @@ export class ConvertApp {
     );
     this.uiState.appendStatus("Starting queued conversions...");
 
-    const queue = [...this.queuedInputPaths];
     try {
-      let successCount = 0;
-      let failureCount = 0;
+      const requests = this.queuedInputPaths.map((inputPath) => this.createConvertRequest(inputPath));
+      const jobs = await this.tauriApi.enqueueJobs(requests);
+      await this.trackJobs(jobs);
+    } catch (error) {
+      this.uiState.appendStatus(`Failed to queue conversions: ${toErrorMessage(error)}`);
+      this.batch = null;
+      this.setConverting(false);
+    }
+  }
 
-      for (const [index, inputPath] of queue.entries()) {
-        if (this.cancelRequested) {
-          break;
-        }
+  private async resumeQueuedJobs(): Promise<void> {
+    try {
+      const resetNotice = await this.tauriApi.takeQueueResetNotice();
+      if (resetNotice) {
+        this.uiState.appendStatus(resetNotice);
+      }
+      const jobs = await this.tauriApi.listJobs();
+      const resumedJobs = await this.offerInterruptedJobs(jobs);
+      const unfinishedJobs = [...jobs.filter((job) => !isFinishedJob(job)), ...resumedJobs];
+      if (unfinishedJobs.length === 0 || this.isConverting) {
+        return;
+      }
 
-        this.uiState.setProgress(0, `Queue ${index + 1}/${queue.length} | Waiting`);
-        this.progressPresenter.reset();
+      this.setConverting(true);
+      this.uiState.appendStatus(`Following ${formatFileCount(unfinishedJobs.length)} still in the queue.`);
+      await this.trackJobs(unfinishedJobs);
+    } catch (error) {
+      this.uiState.appendStatus(`Could not read the conversion queue: ${toErrorMessage(error)}`);
+    }
+  }
+
+  private async offerInterruptedJobs(jobs: ConversionJob[]): Promise<ConversionJob[]> {
+    const interruptedCount = jobs.filter((job) => job.status === "interrupted").length;
+    if (interruptedCount === 0) {
+      return [];
+    }
+
+    const shouldResume = window.confirm(
+      `The last batch stopped before ${formatFileCount(interruptedCount)} finished. Resume it now?`
+    );
+    if (!shouldResume) {
+      await this.tauriApi.discardInterruptedJobs();
+      this.uiState.appendStatus(`Discarded ${formatFileCount(interruptedCount)} from the interrupted batch.`);
+      return [];
+    }
+
+    return this.tauriApi.resumeInterruptedJobs();
+  }
+
+  private async trackJobs(jobs: ConversionJob[]): Promise<void> {
+    const jobIds = jobs.map((job) => job.id);
+    this.batch = {
+      jobIds,
+      pendingJobIds: new Set(jobIds),
+      lastStatuses: new Map(),
+      successCount: 0,
+      failureCount: 0,
+      skippedCount: 0,
+      stoppedCount: 0
+    };
+
+    // Job updates can arrive before enqueueJobs resolves, so replay the current state.
+    const latestJobs = await this.tauriApi.listJobs();
+    for (const job of latestJobs) {
+      this.handleJobUpdate(job);
+    }
+  }
+
+  private handleJobUpdate(job: ConversionJob): void {
+    const batch = this.batch;
+    if (!batch || !batch.pendingJobIds.has(job.id) || batch.lastStatuses.get(job.id) === job.status) {
+      return;
+    }
+
+    batch.lastStatuses.set(job.id, job.status);
+    const position = `Queue ${batch.jobIds.indexOf(job.id) + 1}/${batch.jobIds.length}`;
 
-        this.uiState.appendStatus(`Queue ${index + 1}/${queue.length}: ${basename(inputPath)}`);
-
-        const request = this.createConvertRequest(inputPath);
-
-        try {
-          const result = await this.tauriApi.convertVideo(request);
-          if (this.cancelRequested) {
-            break;
-          }
-
-          successCount += 1;
-          this.uiState.setProgress(100, `Queue ${index + 1}/${queue.length} | Done`);
-          this.uiState.appendStatus(`Done: ${result.outputPath}`);
-          this.uiState.appendStatus(`Encoder used: ${result.av1Encoder}`);
-          this.uiState.appendStatus(`Target size: ${result.targetSizeBytes} bytes`);
-          this.uiState.appendStatus(`Video bitrate: ${result.videoBitrateKbps} kbps`);
-          this.uiState.appendStatus(`Audio bitrate: ${result.audioBitrateKbps} kbps`);
-        } catch (error) {
-          const rawMessage = toRawErrorMessage(error);
-          const message = toErrorMessage(error);
-          if (isCancellationErrorMessage(rawMessage) || this.cancelRequested) {
-            this.cancelRequested = true;
-            this.uiState.appendStatus("Canceled by user.");
-            break;
-          }
-
-          failureCount += 1;
-          this.uiState.appendStatus(`Failed: ${message}`);
+    switch (job.status) {
+      case "queued":
+      case "interrupted":
+        return;
+      case "running":
+        this.progressPresenter.reset();
+        this.uiState.setProgress(0, `${position} | Waiting`);
+        this.uiState.appendStatus(`${position}: ${basename(job.request.inputPath)}`);
+        return;
+      case "succeeded":
+        batch.successCount += 1;
+        this.uiState.setProgress(100, `${position} | Done`);
+        if (job.result) {
+          this.reportResult(job.result);
         }
+        break;
+      case "failed":
+        batch.failureCount += 1;
+        this.uiState.appendStatus(`Failed: ${toErrorMessage(job.error ?? "Unknown error")}`);
+        break;
+      case "canceled":
+        if (!this.cancelRequested) {
+          this.cancelRequested = true;
+          this.uiState.appendStatus("Canceled by user.");
+        }
+        break;
+      case "skipped":
+        batch.skippedCount += 1;
+        this.uiState.appendStatus(
+          `Skipped ${basename(job.request.inputPath)}: ${toErrorMessage(job.error ?? "Skipped")}`
+        );
+        break;
+      case "stopped":
+        batch.stoppedCount += 1;
+        break;
+    }
+
+    batch.pendingJobIds.delete(job.id);
+    if (batch.pendingJobIds.size === 0) {
+      this.finishBatch(batch);
+    }
+  }
+
+  private handleQueueChange(jobs: ConversionJob[]): void {
+    const batch = this.batch;
+    if (!batch) {
+      return;
+    }
+
+    const queuedIds = new Set(jobs.map((job) => job.id));
+    for (const jobId of [...batch.pendingJobIds]) {
+      if (!queuedIds.has(jobId)) {
+        batch.pendingJobIds.delete(jobId);
+        this.uiState.appendStatus(`Queue ${batch.jobIds.indexOf(jobId) + 1}/${batch.jobIds.length}: removed`);
       }
+    }
+    if (batch.pendingJobIds.size === 0) {
+      this.finishBatch(batch);
+    }
+  }
 
-      if (this.cancelRequested) {
-        const remainingCount = remainingQueueCount(queue.length, successCount, failureCount);
+  private reportResult(result: ConvertResult): void {
+    this.uiState.appendStatus(`Done: ${result.outputPath}`);
+    this.uiState.appendStatus(`Encoder used: ${result.av1Encoder}`);
+    if (result.targetPolicy) {
+      this.uiState.appendStatus(`Target policy: ${formatTargetPolicy(result.targetPolicy)}`);
+    }
+    if (result.targetSizeBytes !== null) {
+      this.uiState.appendStatus(`Target size: ${result.targetSizeBytes} bytes`);
+    }
+    if (result.qualityLevel !== null) {
+      this.uiState.appendStatus(`Quality level: ${result.qualityLevel}`);
+    }
+    if (result.vmafSearch) {
+      const search = result.vmafSearch;
+      for (const step of search.steps) {
+        const scores = step.sampleScores.map((score) => score.toFixed(2)).join(", ");
         this.uiState.appendStatus(
-          `Queue canceled. Completed: ${successCount}, Failed: ${failureCount}, Remaining: ${remainingCount}`
+          `VMAF at level ${step.qualityLevel}: ${step.meanScore.toFixed(2)} (samples: ${scores})`
         );
-      } else {
-        this.uiState.appendStatus(`Queue complete. Succeeded: ${successCount}, Failed: ${failureCount}`);
       }
-    } finally {
-      if (this.cancelRequested) {
-        this.progressPresenter.reset();
-        this.uiState.setProgress(0, "Canceled");
-      } else {
-        this.progressPresenter.flushNow();
-      }
-      this.setConverting(false);
+      this.uiState.appendStatus(
+        search.targetMet
+          ? `VMAF target ${search.targetScore} met at quality level ${search.qualityLevel}`
+          : `VMAF target ${search.targetScore} not reached; used quality level ${search.qualityLevel}`
+      );
+    }
+    this.uiState.appendStatus(
+      result.attempts.length > 0
+        ? `Output size: ${result.outputSizeBytes} bytes (${result.attempts.length} encode attempt(s))`
+        : `Output size: ${result.outputSizeBytes} bytes`
+    );
+    if (result.videoBitrateKbps !== null) {
+      this.uiState.appendStatus(`Video bitrate: ${result.videoBitrateKbps} kbps`);
+    }
+    this.uiState.appendStatus(
+      `Audio bitrate: ${result.audioBitrateKbps} kbps across ${result.audioTrackCount} track(s)`
+    );
+  }
+
+  private finishBatch(batch: JobBatch): void {
+    this.batch = null;
+    const { successCount, failureCount, skippedCount, stoppedCount } = batch;
+    const skippedText = skippedCount > 0 ? `, Skipped: ${skippedCount}` : "";
+
+    if (this.cancelRequested) {
+      const remainingCount = remainingQueueCount(
+        batch.jobIds.length,
+        successCount + skippedCount,
+        failureCount
+      );
+      this.uiState.appendStatus(
+        `Queue canceled. Completed: ${successCount}, Failed: ${failureCount}${skippedText}, Remaining: ${remainingCount}`
+      );
+      this.progressPresenter.reset();
+      this.uiState.setProgress(0, "Canceled");
+    } else if (stoppedCount > 0) {
+      this.uiState.appendStatus(
+        `Queue stopped. Succeeded: ${successCount}, Failed: ${failureCount}${skippedText}, Not started: ${stoppedCount}`
+      );
+      this.progressPresenter.flushNow();
+    } else {
+      this.uiState.appendStatus(
+        `Queue complete. Succeeded: ${successCount}, Failed: ${failureCount}${skippedText}`
+      );
+      this.progressPresenter.flushNow();
     }
+    this.setConverting(false);
   }
 
   private async handleCancel(): Promise<void> {
@@ -248,17 +551,96 @@ export class ConvertApp {
     }
   }
 
+  private async handleSkipCurrent(): Promise<void> {
+    const batch = this.batch;
+    if (!this.isConverting || this.cancelRequested || !batch) {
+      return;
+    }
+
+    const runningJobIds = [...batch.lastStatuses]
+      .filter(([, status]) => status === "running")
+      .map(([jobId]) => jobId);
+    for (const jobId of runningJobIds) {
+      try {
+        await this.tauriApi.skipJob(jobId);
+      } catch (error) {
+        if (!isNoActiveConversionErrorMessage(toRawErrorMessage(error))) {
+          this.uiState.appendStatus(`Skip request failed: ${toErrorMessage(error)}`);
+        }
+      }
+    }
+  }
+
+  private async handleStopAfterCurrent(): Promise<void> {
+    if (!this.isConverting || this.cancelRequested || this.stopAfterCurrentRequested) {
+      return;
+    }
+
+    this.stopAfterCurrentRequested = true;
+    this.syncButtonState();
+    this.uiState.appendStatus("Stopping after the current file...");
+
+    try {
+      await this.tauriApi.stopAfterCurrent();
+    } catch (error) {
+      this.stopAfterCurrentRequested = false;
+      this.syncButtonState();
+      this.uiState.appendStatus(`Stop request failed: ${toErrorMessage(error)}`);
+    }
+  }
+
+  private async handleTogglePause(): Promise<void> {
+    if (!this.isConverting || this.cancelRequested) {
+      return;
+    }
+
+    const pausing = !this.isPaused;
+    try {
+      if (pausing) {
+        await this.tauriApi.pauseConversion();
+      } else {
+        await this.tauriApi.resumeConversion();
+      }
+    } catch (error) {
+      this.uiState.appendStatus(
+        `${pausing ? "Pause" : "Resume"} request failed: ${toErrorMessage(error)}`
+      );
+      return;
+    }
+
+    this.isPaused = pausing;
+    this.syncButtonState();
+    this.uiState.appendStatus(pausing ? "Conversion paused." : "Conversion resumed.");
+  }
+
   private handleProgress(payload: ConvertProgressPayload): void {
     if (!this.isConverting || this.cancelRequested) {
       return;
     }
 
-    this.progressPresenter.queue(payload);
+    const jobId = payload.jobId;
+    if (typeof jobId !== "number") {
+      this.progressPresenter.queue(payload);
+      return;
+    }
+
+    const batch = this.batch;
+    if (!batch?.pendingJobIds.has(jobId)) {
+      return;
+    }
+
+    const position = `Queue ${batch.jobIds.indexOf(jobId) + 1}/${batch.jobIds.length}`;
+    this.progressPresenter.queue({
+      ...payload,
+      label: payload.label ? `${position} | ${payload.label}` : position
+    });
   }
 
   private setConverting(value: boolean): void {
     if (!value) {
       this.cancelRequested = false;
+      this.stopAfterCurrentRequested = false;
+      this.isPaused = false;
     }
 
     this.isConverting = value;
@@ -271,6 +653,9 @@ export class ConvertApp {
     const hasSelectedInput = this.queuedInputPaths.length > 0;
 
     this.elements.browseInputButton.disabled = this.isConverting;
+    this.elements.browseFolderButton.disabled = this.isConverting;
+    this.elements.presetLevel.disabled = this.isConverting;
+    this.elements.recheckEncodersButton.disabled = this.isConverting || this.isLoadingEncoder;
     this.elements.convertButton.disabled =
       this.isConverting ||
       this.isLoadingEncoder ||
@@ -282,6 +667,14 @@ export class ConvertApp {
     );
     this.elements.cancelButton.hidden = !this.isConverting;
     this.elements.cancelButton.disabled = !this.isConverting || this.cancelRequested;
+    this.elements.skipCurrentButton.hidden = !this.isConverting;
+    this.elements.skipCurrentButton.disabled = !this.isConverting || this.cancelRequested;
+    this.elements.stopAfterCurrentButton.hidden = !this.isConverting;
+    this.elements.stopAfterCurrentButton.disabled =
+      !this.isConverting || this.cancelRequested || this.stopAfterCurrentRequested;
+    this.elements.pauseResumeButton.hidden = !this.isConverting;
+    this.elements.pauseResumeButton.disabled = !this.isConverting || this.cancelRequested;
+    this.elements.pauseResumeButton.textContent = this.isPaused ? "Resume" : "Pause";
   }
 
   private createConvertRequest(inputPath: string): ConvertRequest {
@@ -289,9 +682,18 @@ export class ConvertApp {
     if (this.selectedAv1Encoder) {
       request.av1Encoder = this.selectedAv1Encoder;
     }
+    request.presetLevel = this.elements.presetLevel.valueAsNumber;
+    if (this.sourceTree) {
+      request.sourceRoot = this.sourceTree.sourceRoot;
+      request.outputDir = this.sourceTree.outputDir;
+    }
     return request;
   }
 
+  private syncPresetLabel(): void {
+    this.elements.presetLabel.textContent = formatPresetLevel(this.elements.presetLevel.valueAsNumber);
+  }
+
   private setEncoderStatus(message: string, tone: EncoderStatusTone): void {
     this.elements.encoderStatus.textContent = message;
     this.elements.encoderStatus.classList.remove("ok", "warn");
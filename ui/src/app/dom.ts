@@ -10,9 +10,16 @@ export interface AppElements {
   appRoot: HTMLElement;
   inputPath: HTMLInputElement;
   browseInputButton: HTMLButtonElement;
+  browseFolderButton: HTMLButtonElement;
   encoderStatus: HTMLParagraphElement;
+  recheckEncodersButton: HTMLButtonElement;
+  presetLevel: HTMLInputElement;
+  presetLabel: HTMLSpanElement;
   convertButton: HTMLButtonElement;
   cancelButton: HTMLButtonElement;
+  skipCurrentButton: HTMLButtonElement;
+  stopAfterCurrentButton: HTMLButtonElement;
+  pauseResumeButton: HTMLButtonElement;
   progress: HTMLProgressElement;
   progressText: HTMLSpanElement;
   status: HTMLPreElement;
@@ -23,9 +30,16 @@ export function getAppElements(): AppElements {
     appRoot: requireElement<HTMLElement>("appRoot", HTMLElement),
     inputPath: requireElement<HTMLInputElement>("inputPath", HTMLInputElement),
     browseInputButton: requireElement<HTMLButtonElement>("browseInput", HTMLButtonElement),
+    browseFolderButton: requireElement<HTMLButtonElement>("browseFolder", HTMLButtonElement),
     encoderStatus: requireElement<HTMLParagraphElement>("encoderStatus", HTMLParagraphElement),
+    recheckEncodersButton: requireElement<HTMLButtonElement>("recheckEncoders", HTMLButtonElement),
+    presetLevel: requireElement<HTMLInputElement>("presetLevel", HTMLInputElement),
+    presetLabel: requireElement<HTMLSpanElement>("presetLabel", HTMLSpanElement),
     convertButton: requireElement<HTMLButtonElement>("convert", HTMLButtonElement),
     cancelButton: requireElement<HTMLButtonElement>("cancel", HTMLButtonElement),
+    skipCurrentButton: requireElement<HTMLButtonElement>("skipCurrent", HTMLButtonElement),
+    stopAfterCurrentButton: requireElement<HTMLButtonElement>("stopAfterCurrent", HTMLButtonElement),
+    pauseResumeButton: requireElement<HTMLButtonElement>("pauseResume", HTMLButtonElement),
     progress: requireElement<HTMLProgressElement>("progress", HTMLProgressElement),
     progressText: requireElement<HTMLSpanElement>("progressText", HTMLSpanElement),
     status: requireElement<HTMLPreElement>("status", HTMLPreElement)
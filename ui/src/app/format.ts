@@ -1,5 +1,5 @@
 import { toDisplayErrorMessage } from "./conversion-errors.js";
-import type { ConvertProgressPayload } from "./types.js";
+import type { ConvertProgressPayload, EncoderCheck, MediaProbeSummary, TargetPolicy } from "./types.js";
 
 export function toErrorMessage(error: unknown): string {
   return toDisplayErrorMessage(toRawErrorMessage(error));
@@ -33,6 +33,11 @@ export function formatProgressText(payload: ConvertProgressPayload): string {
 
   parts.push(formatPercent(percent));
 
+  if (payload.paused) {
+    parts.push("Paused");
+    return parts.join(" | ");
+  }
+
   if (typeof payload.speed === "number" && payload.speed > 0) {
     parts.push(`${payload.speed.toFixed(2)}x`);
   }
@@ -43,3 +48,57 @@ export function formatProgressText(payload: ConvertProgressPayload): string {
 
   return parts.join(" | ");
 }
+
+const PRESET_LEVEL_LABELS = ["Fastest", "Fast", "Balanced", "Better compression", "Best compression"];
+
+export function formatPresetLevel(level: number): string {
+  return PRESET_LEVEL_LABELS[level - 1] ?? `Level ${level}`;
+}
+
+export function formatTargetPolicy(policy: TargetPolicy): string {
+  switch (policy.kind) {
+    case "ratio":
+      return `${policy.percent}% of source size`;
+    case "sizeMb":
+      return `${policy.megabytes} MB output size`;
+    case "maxBitrate":
+      return `${policy.kbps} kbps maximum bitrate`;
+  }
+}
+
+export function formatEncoderCheck(check: EncoderCheck): string {
+  if (!check.usable) {
+    return `${check.encoder}: test encode failed (${check.error ?? "unknown error"})`;
+  }
+  return check.error
+    ? `${check.encoder}: test encode passed, options unknown (${check.error})`
+    : `${check.encoder}: test encode passed`;
+}
+
+export function formatMediaSummary(summary: MediaProbeSummary): string {
+  const video = summary.video;
+  const parts = [
+    `${Math.round(summary.durationSec)}s`,
+    `${video.codec} ${video.width}x${video.height}${video.hdr ? " HDR" : ""}`
+  ];
+
+  if (summary.bitRateKbps !== null) {
+    parts.push(`${summary.bitRateKbps} kbps`);
+  }
+
+  parts.push(`${summary.audioTracks.length} audio`);
+  parts.push(`${summary.subtitleTracks.length} subtitle`);
+  return parts.join(" | ");
+}
+
+export function formatPlannedOutput(summary: MediaProbeSummary): string {
+  const planned = summary.plannedOutput;
+  if (planned === null) {
+    return `not possible (${summary.planError ?? "unknown reason"})`;
+  }
+  if (planned.targetSizeBytes !== null && planned.videoBitrateKbps !== null) {
+    return `${planned.targetSizeBytes} bytes at ${planned.videoBitrateKbps} kbps video`;
+  }
+
+  return planned.qualityLevel !== null ? `quality level ${planned.qualityLevel}` : "unknown";
+}
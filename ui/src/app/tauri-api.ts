@@ -1,9 +1,15 @@
 import type {
+  ConversionJob,
+  ConversionPreview,
   ConvertProgressPayload,
   ConvertRequest,
   ConvertResult,
   DialogFilter,
   DialogOpenOptions,
+  EncoderCheck,
+  FolderScanRequest,
+  FolderScanResult,
+  MediaProbeSummary,
   TauriDialogApi,
   TauriGlobalApi
 } from "./types.js";
@@ -14,13 +20,33 @@ const VIDEO_FILE_FILTERS: DialogFilter[] = [
 
 export interface AppTauriApi {
   pickAutoAv1Encoder(): Promise<string>;
+  refreshEncoders(): Promise<EncoderCheck[]>;
   convertVideo(request: ConvertRequest): Promise<ConvertResult>;
+  probeMedia(request: ConvertRequest): Promise<MediaProbeSummary>;
+  previewConversion(request: ConvertRequest): Promise<ConversionPreview>;
+  scanFolders(request: FolderScanRequest): Promise<FolderScanResult>;
+  enqueueJobs(requests: ConvertRequest[]): Promise<ConversionJob[]>;
+  listJobs(): Promise<ConversionJob[]>;
+  removeJob(jobId: number): Promise<void>;
+  reorderJobs(jobIds: number[]): Promise<void>;
+  setConcurrencyLimit(limit: number): Promise<void>;
+  resumeInterruptedJobs(): Promise<ConversionJob[]>;
+  discardInterruptedJobs(): Promise<void>;
+  takeQueueResetNotice(): Promise<string | null>;
   cancelConversion(): Promise<void>;
+  cancelJob(jobId: number): Promise<void>;
+  skipJob(jobId: number): Promise<void>;
+  stopAfterCurrent(): Promise<void>;
+  pauseConversion(jobId?: number): Promise<void>;
+  resumeConversion(jobId?: number): Promise<void>;
   showMainWindow(): Promise<void>;
   openInputDialog(): Promise<string[]>;
+  openFolderDialog(title: string): Promise<string | null>;
   listenConvertProgress(
     listener: (payload: ConvertProgressPayload) => void
   ): Promise<() => void | Promise<void>>;
+  listenJobUpdates(listener: (job: ConversionJob) => void): Promise<() => void | Promise<void>>;
+  listenQueueChanges(listener: (jobs: ConversionJob[]) => void): Promise<() => void | Promise<void>>;
 }
 
 export function createTauriApi(tauriGlobal: TauriGlobalApi | undefined = window.__TAURI__): AppTauriApi {
@@ -35,11 +61,45 @@ export function createTauriApi(tauriGlobal: TauriGlobalApi | undefined = window.
 
   return {
     pickAutoAv1Encoder: () => invoke<string>("pick_auto_av1_encoder"),
+    refreshEncoders: () => invoke<EncoderCheck[]>("refresh_encoders"),
 
     convertVideo: (request: ConvertRequest) => invoke<ConvertResult>("convert_video", { request }),
 
+    probeMedia: (request: ConvertRequest) => invoke<MediaProbeSummary>("probe_media", { request }),
+
+    previewConversion: (request: ConvertRequest) =>
+      invoke<ConversionPreview>("preview_conversion", { request }),
+
+    scanFolders: (request: FolderScanRequest) => invoke<FolderScanResult>("scan_folders", { request }),
+
+    enqueueJobs: (requests: ConvertRequest[]) => invoke<ConversionJob[]>("enqueue_jobs", { requests }),
+
+    listJobs: () => invoke<ConversionJob[]>("list_jobs"),
+
+    removeJob: (jobId: number) => invoke<void>("remove_job", { jobId }),
+
+    reorderJobs: (jobIds: number[]) => invoke<void>("reorder_jobs", { jobIds }),
+
+    setConcurrencyLimit: (limit: number) => invoke<void>("set_concurrency_limit", { limit }),
+
+    resumeInterruptedJobs: () => invoke<ConversionJob[]>("resume_interrupted_jobs"),
+
+    discardInterruptedJobs: () => invoke<void>("discard_interrupted_jobs"),
+
+    takeQueueResetNotice: () => invoke<string | null>("take_queue_reset_notice"),
+
     cancelConversion: () => invoke<void>("cancel_conversion"),
 
+    cancelJob: (jobId: number) => invoke<void>("cancel_job", { jobId }),
+
+    skipJob: (jobId: number) => invoke<void>("skip_job", { jobId }),
+
+    stopAfterCurrent: () => invoke<void>("stop_after_current"),
+
+    pauseConversion: (jobId?: number) => invoke<void>("pause_conversion", { jobId: jobId ?? null }),
+
+    resumeConversion: (jobId?: number) => invoke<void>("resume_conversion", { jobId: jobId ?? null }),
+
     showMainWindow: () => invoke<void>("show_main_window"),
 
     openInputDialog: () => {
@@ -47,12 +107,30 @@ export function createTauriApi(tauriGlobal: TauriGlobalApi | undefined = window.
       return openInputDialogWithFallback(tauriGlobal.dialog, options, invokeDialogOpen);
     },
 
+    openFolderDialog: async (title: string) => {
+      const options = { multiple: false, filters: [], directory: true, title };
+      const [folder] = await openInputDialogWithFallback(tauriGlobal.dialog, options, invokeDialogOpen);
+      return folder ?? null;
+    },
+
     listenConvertProgress(
       listener: (payload: ConvertProgressPayload) => void
     ): Promise<() => void | Promise<void>> {
       return listen("convert-progress", (event: { payload: ConvertProgressPayload }) => {
         listener(event.payload ?? {});
       });
+    },
+
+    listenJobUpdates(listener: (job: ConversionJob) => void): Promise<() => void | Promise<void>> {
+      return listen("job-updated", (event: { payload: ConversionJob }) => {
+        listener(event.payload);
+      });
+    },
+
+    listenQueueChanges(listener: (jobs: ConversionJob[]) => void): Promise<() => void | Promise<void>> {
+      return listen("queue-changed", (event: { payload: ConversionJob[] }) => {
+        listener(event.payload ?? []);
+      });
     }
   };
 }
@@ -1,21 +1,155 @@
+export type TargetPolicy =
+  | { kind: "ratio"; percent: number }
+  | { kind: "sizeMb"; megabytes: number }
+  | { kind: "maxBitrate"; kbps: number };
+
+export type CollisionPolicy = "skip" | "overwrite" | "autoNumber";
+
 export interface ConvertRequest {
   inputPath: string;
   av1Encoder?: string;
+  targetPolicy?: TargetPolicy;
+  qualityLevel?: number;
+  presetLevel?: number;
+  vmafTarget?: number;
+  twoPass?: boolean;
+  maxEncodeAttempts?: number;
+  collisionPolicy?: CollisionPolicy;
+  outputDir?: string;
+  outputTemplate?: string;
+  sourceRoot?: string;
+}
+
+export interface EncodeAttempt {
+  attempt: number;
+  videoBitrateKbps: number;
+  outputSizeBytes: number;
+  withinTarget: boolean;
+}
+
+export interface VmafSearchStep {
+  qualityLevel: number;
+  sampleScores: number[];
+  meanScore: number;
+}
+
+export interface VmafSearchResult {
+  targetScore: number;
+  qualityLevel: number;
+  targetMet: boolean;
+  sampleStartsSec: number[];
+  steps: VmafSearchStep[];
 }
 
 export interface ConvertResult {
   outputPath: string;
-  targetSizeBytes: number;
-  videoBitrateKbps: number;
+  targetPolicy: TargetPolicy | null;
+  targetSizeBytes: number | null;
+  qualityLevel: number | null;
+  outputSizeBytes: number;
+  videoBitrateKbps: number | null;
   audioBitrateKbps: number;
+  audioTrackCount: number;
+  subtitleTrackCount: number;
   av1Encoder: string;
+  twoPass: boolean;
+  attempts: EncodeAttempt[];
+  vmafSearch: VmafSearchResult | null;
+}
+
+export interface VideoTrackSummary {
+  index: number;
+  codec: string;
+  width: number;
+  height: number;
+  frameRate: number | null;
+  bitRateKbps: number | null;
+  hdr: boolean;
+}
+
+export interface AudioTrackSummary {
+  index: number;
+  codec: string;
+  language: string | null;
+  title: string | null;
+  channels: number | null;
+  bitRateKbps: number | null;
+  default: boolean;
+}
+
+export interface SubtitleTrackSummary {
+  index: number;
+  codec: string;
+  language: string | null;
+  title: string | null;
+  forced: boolean;
+  kept: boolean;
+}
+
+export interface PlannedOutput {
+  outputPath: string;
+  targetPolicy: TargetPolicy | null;
+  targetSizeBytes: number | null;
+  qualityLevel: number | null;
+  videoBitrateKbps: number | null;
+  audioBitrateKbps: number;
+  encoderCandidates: string[];
+  twoPass: boolean;
+}
+
+export interface MediaProbeSummary {
+  inputPath: string;
+  container: string;
+  durationSec: number;
+  sizeBytes: number | null;
+  bitRateKbps: number | null;
+  video: VideoTrackSummary;
+  audioTracks: AudioTrackSummary[];
+  subtitleTracks: SubtitleTrackSummary[];
+  chapterCount: number;
+  plannedOutput: PlannedOutput | null;
+  planError: string | null;
+}
+
+export interface FfmpegInvocation {
+  label: string;
+  args: string[];
+  runtimeValues: string[];
+}
+
+export interface EncoderPreview {
+  encoder: string;
+  twoPass: boolean;
+  invocations: FfmpegInvocation[];
+}
+
+export interface ConversionPreview {
+  inputPath: string;
+  outputPath: string;
+  ffmpegPath: string;
+  ffprobePath: string;
+  encoderCandidates: string[];
+  encoders: EncoderPreview[];
+}
+
+export type JobStatus = "queued" | "running" | "succeeded" | "failed" | "canceled" | "skipped" | "stopped" | "interrupted";
+
+export interface ConversionJob {
+  id: number;
+  request: ConvertRequest;
+  status: JobStatus;
+  result: ConvertResult | null;
+  error: string | null;
+  partialOutputPath: string | null;
 }
 
 export interface ConvertProgressPayload {
+  jobId?: number | null;
   percent?: number;
   label?: string;
   speed?: number;
   etaSeconds?: number;
+  paused?: boolean;
 }
 
 export interface DialogFilter {
@@ -23,9 +157,62 @@ export interface DialogFilter {
   extensions: string[];
 }
 
+export type RateControlMode = "constantBitrate" | "variableBitrate" | "constantQuality";
+
+export interface EncoderOption {
+  name: string;
+  valueType: string;
+  min: number | null;
+  max: number | null;
+  values: string[];
+}
+
+export interface EncoderDescription {
+  name: string;
+  options: EncoderOption[];
+  rateControlModes: RateControlMode[];
+}
+
+export interface EncoderCheck {
+  encoder: string;
+  usable: boolean;
+  error: string | null;
+  description: EncoderDescription | null;
+}
+
+export interface FolderScanRequest {
+  folders: string[];
+  recursive?: boolean;
+  extensions?: string[];
+  includeGlobs?: string[];
+  excludeGlobs?: string[];
+  minSizeBytes?: number;
+  minDurationSec?: number;
+  skipConverted?: boolean;
+}
+
+export interface ScannedFile {
+  inputPath: string;
+  sourceRoot: string;
+  sizeBytes: number;
+  durationSec: number | null;
+}
+
+export interface SkippedFile {
+  path: string;
+  reason: string;
+}
+
+export interface FolderScanResult {
+  files: ScannedFile[];
+  skipped: SkippedFile[];
+}
+
 export interface DialogOpenOptions {
   multiple: boolean;
   filters: DialogFilter[];
+  directory?: boolean;
+  title?: string;
 }
 
 export interface TauriDialogApi {